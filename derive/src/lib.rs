@@ -0,0 +1,387 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Provides derive macros for the `fig` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DataEnum, DataStruct, DeriveInput, Fields, LitStr};
+
+/// Converts the given `PascalCase` name into `snake_case`.
+fn to_snake_case(name: &str) -> String {
+    let characters = name.chars().collect::<Vec<_>>();
+    let mut string = String::with_capacity(name.len() + 4);
+
+    for (index, &character) in characters.iter().enumerate() {
+        if character.is_uppercase() && index > 0 {
+            let previous = characters[index - 1];
+            let next = characters.get(index + 1).copied();
+
+            if previous.is_lowercase()
+                || previous.is_numeric()
+                || (previous.is_uppercase() && next.is_some_and(char::is_lowercase))
+            {
+                string.push('_');
+            }
+        }
+
+        string.extend(character.to_lowercase());
+    }
+
+    string
+}
+
+/// Every strict or reserved keyword in the latest edition.
+///
+/// This must match the list used by `fig` itself, which is checked by the tests below.
+const KEYWORDS: &[&str] = &[
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro",
+    "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super",
+    "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that may not be used as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["Self", "crate", "self", "super"];
+
+/// Validates that the given configuration key is a valid Rust identifier, as checked by `fig::Cfg::try_new`.
+fn validate_key(key: &str) -> Result<(), String> {
+    let (name, raw) = key.strip_prefix("r#").map_or((key, false), |name| (name, true));
+    let mut characters = name.chars();
+
+    match characters.next() {
+        None => return Err("identifiers must not be empty".into()),
+        Some('_') if name.len() == 1 => return Err("`_` is not a valid identifier".into()),
+        Some(c) if c != '_' && !unicode_ident::is_xid_start(c) => {
+            return Err(format!("identifiers must not start with {c:?}"));
+        }
+        Some(_) => {}
+    }
+
+    if let Some(c) = characters.find(|c| !unicode_ident::is_xid_continue(*c)) {
+        return Err(format!("identifiers must not contain {c:?}"));
+    }
+
+    if raw && NON_RAW_KEYWORDS.contains(&name) {
+        Err("this keyword cannot be a raw identifier".into())
+    } else if !raw && KEYWORDS.contains(&name) {
+        Err("keywords must be written as raw identifiers".into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the given configuration key, or an error at the given span if it is not a valid identifier.
+fn checked_key(key: String, span: proc_macro2::Span) -> syn::Result<String> {
+    match self::validate_key(&key) {
+        Ok(()) => Ok(key),
+        Err(reason) => Err(syn::Error::new(span, format!("configuration key {key:?} is invalid: {reason}"))),
+    }
+}
+
+/// The options that may be provided through `#[fig(...)]` attributes.
+#[derive(Default)]
+struct Options {
+    /// The configuration key, set through `key = "..."`.
+    key: Option<LitStr>,
+    /// The configuration value or key, set through `rename = "..."`.
+    rename: Option<LitStr>,
+    /// The environment variable key, set through `env = "..."`.
+    env: Option<LitStr>,
+}
+
+impl Options {
+    /// Parses the options from the given attributes, only accepting the listed option names.
+    fn parse(attributes: &[Attribute], allowed: &[&str]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attribute in attributes.iter().filter(|a| a.path().is_ident("fig")) {
+            attribute.parse_nested_meta(|meta| {
+                let slot = match meta.path.get_ident().map(ToString::to_string).as_deref() {
+                    Some(name @ "key") if allowed.contains(&name) => &mut options.key,
+                    Some(name @ "rename") if allowed.contains(&name) => &mut options.rename,
+                    Some(name @ "env") if allowed.contains(&name) => &mut options.env,
+                    _ => return Err(meta.error(format!("expected one of: {}", allowed.join(", ")))),
+                };
+
+                if slot.is_some() {
+                    return Err(meta.error("duplicate option"));
+                }
+
+                *slot = Some(meta.value()?.parse()?);
+
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+/// Maps an enum onto a single configuration, or a struct onto one configuration per field.
+///
+/// For enums, this implements `fig::CfgEnum` and `FromStr`. Every variant must be a unit variant, and is assigned a
+/// `snake_case` value by default, which may be changed using `#[fig(rename = "...")]`. The configuration key defaults
+/// to the `snake_case` name of the enum, and may be changed using `#[fig(key = "...")]`.
+///
+/// For structs, this implements `fig::CfgSchema`. Every field must implement `fig::CfgField`, and is declared using
+/// its name as the key, which may be changed using `#[fig(rename = "...")]`. Each field is read from an environment
+/// variable named after its upper-cased key by default, which may be changed using `#[fig(env = "...")]`.
+///
+/// Configuration keys must be valid Rust identifiers, which is checked at compile-time.
+#[proc_macro_derive(Cfg, attributes(fig))]
+pub fn derive_cfg(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    self::expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Implements the traits for the given enum or struct.
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    match &input.data {
+        Data::Enum(data) => self::expand_enum(input, data),
+        Data::Struct(data) => self::expand_struct(input, data),
+        Data::Union(_) => Err(syn::Error::new(input.ident.span(), "unions are not supported")),
+    }
+}
+
+/// Implements `CfgEnum` and `FromStr` for the given enum.
+fn expand_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream2> {
+    let options = Options::parse(&input.attrs, &["key"])?;
+    let key = match options.key {
+        Some(key) => self::checked_key(key.value(), key.span())?,
+        None => self::checked_key(self::to_snake_case(&input.ident.to_string()), input.ident.span())?,
+    };
+
+    if data.variants.is_empty() {
+        return Err(syn::Error::new(input.ident.span(), "at least one variant should be provided"));
+    }
+
+    let mut idents = Vec::with_capacity(data.variants.len());
+    let mut values = Vec::<String>::with_capacity(data.variants.len());
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(variant.fields.span(), "only unit variants are supported"));
+        }
+
+        let options = Options::parse(&variant.attrs, &["rename"])?;
+        let value = options.rename.map_or_else(|| self::to_snake_case(&variant.ident.to_string()), |v| v.value());
+
+        if values.contains(&value) {
+            return Err(syn::Error::new(variant.ident.span(), format!("duplicate configuration value {value:?}")));
+        }
+
+        idents.push(&variant.ident);
+        values.push(value);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::fig::CfgEnum for #ident #type_generics #where_clause {
+            const KEY: &'static str = #key;
+
+            const VALUES: &'static [&'static str] = &[#(#values),*];
+
+            fn as_str(&self) -> &'static str {
+                match self {
+                    #(Self::#idents => #values,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::str::FromStr for #ident #type_generics #where_clause {
+            type Err = ::fig::Error;
+
+            fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+                match value {
+                    #(#values => ::core::result::Result::Ok(Self::#idents),)*
+                    _ => ::core::result::Result::Err(::fig::Error::Unassignable {
+                        key: ::core::convert::Into::into(#key),
+                        value: ::core::option::Option::Some(::core::convert::Into::into(value)),
+                        allowed: <Self as ::fig::CfgEnum>::VALUES.iter().map(|&v| ::core::convert::Into::into(v)).collect(),
+                    }),
+                }
+            }
+        }
+    })
+}
+
+/// Implements `CfgSchema` for the given struct.
+fn expand_struct(input: &DeriveInput, data: &DataStruct) -> syn::Result<TokenStream2> {
+    Options::parse(&input.attrs, &[])?;
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(data.fields.span(), "only structs with named fields are supported"));
+    };
+
+    let mut idents = Vec::with_capacity(fields.named.len());
+    let mut types = Vec::with_capacity(fields.named.len());
+    let mut keys = Vec::with_capacity(fields.named.len());
+    let mut variables = Vec::with_capacity(fields.named.len());
+
+    for field in &fields.named {
+        let options = Options::parse(&field.attrs, &["rename", "env"])?;
+        let Some(ident) = &field.ident else { unreachable!("named fields should have identifiers") };
+        let key = match options.rename {
+            Some(key) => self::checked_key(key.value(), key.span())?,
+            None => ident.to_string(),
+        };
+        let variable = options.env.map_or_else(|| key.trim_start_matches("r#").to_uppercase(), |v| v.value());
+
+        idents.push(ident);
+        types.push(&field.ty);
+        keys.push(key);
+        variables.push(variable);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::fig::CfgSchema for #ident #type_generics #where_clause {
+            fn try_declare() -> ::core::result::Result<(), ::fig::Error> {
+                #(<#types as ::fig::CfgField>::try_declare(#keys)?;)*
+
+                ::core::result::Result::Ok(())
+            }
+
+            fn try_set(&self) -> ::core::result::Result<(), ::fig::Error> {
+                #(<#types as ::fig::CfgField>::try_set(&self.#idents, #keys)?;)*
+
+                ::core::result::Result::Ok(())
+            }
+
+            fn try_from_env() -> ::core::result::Result<Self, ::fig::Error> {
+                ::core::result::Result::Ok(Self {
+                    #(#idents: <#types as ::fig::CfgField>::try_from_env(#keys, #variables)?,)*
+                })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{DeriveInput, parse_quote};
+
+    /// Returns the message of the error produced by expanding the given input.
+    fn error(input: &DeriveInput) -> String {
+        super::expand(input).expect_err("the input should be rejected").to_string()
+    }
+
+    #[test]
+    fn names_are_converted_to_snake_case() {
+        assert_eq!(super::to_snake_case("Backend"), "backend");
+        assert_eq!(super::to_snake_case("IoUring"), "io_uring");
+        assert_eq!(super::to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(super::to_snake_case("Utf8Mode"), "utf8_mode");
+    }
+
+    /// Returns the strings listed by the given constant within the given source.
+    fn listed(source: &str, name: &str) -> Vec<String> {
+        let start = source.find(&format!("const {name}: &[&str] = &[")).expect("the constant should be defined");
+        let list = &source[start..start + source[start..].find("];").expect("the constant should be closed")];
+
+        list.split('"').skip(1).step_by(2).map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn keywords_match_the_main_crate() {
+        let ours = include_str!("lib.rs");
+        let theirs = include_str!("../../src/ident.rs");
+
+        for name in ["KEYWORDS", "NON_RAW_KEYWORDS"] {
+            assert!(!listed(theirs, name).is_empty(), "{name} should not be empty");
+            assert_eq!(listed(ours, name), listed(theirs, name), "{name} should match `src/ident.rs`");
+        }
+    }
+
+    #[test]
+    fn keys_are_validated() {
+        assert_eq!(super::validate_key("backend"), Ok(()));
+        assert_eq!(super::validate_key("_private"), Ok(()));
+        assert_eq!(super::validate_key("r#type"), Ok(()));
+        assert!(super::validate_key("").is_err());
+        assert!(super::validate_key("_").is_err());
+        assert!(super::validate_key("1st").is_err());
+        assert!(super::validate_key("has-dash").is_err());
+        assert!(super::validate_key("type").is_err());
+        assert!(super::validate_key("gen").is_err());
+        assert!(super::validate_key("r#self").is_err());
+    }
+
+    #[test]
+    fn enums_are_expanded() {
+        let input = parse_quote! {
+            #[fig(key = "io_backend")]
+            enum Backend {
+                IoUring,
+                #[fig(rename = "kqueue")]
+                Kq,
+            }
+        };
+        let output = super::expand(&input).expect("the enum should be accepted").to_string();
+
+        assert!(output.contains(r#"const KEY : & 'static str = "io_backend""#));
+        assert!(output.contains(r#"& ["io_uring" , "kqueue"]"#));
+    }
+
+    #[test]
+    fn structs_are_expanded() {
+        let input = parse_quote! {
+            struct Config {
+                #[fig(rename = "r#async", env = "ASYNC_RUNTIME")]
+                runtime: bool,
+                backend: Option<Backend>,
+            }
+        };
+        let output = super::expand(&input).expect("the struct should be accepted").to_string();
+
+        assert!(output.contains(r#"try_from_env ("r#async" , "ASYNC_RUNTIME")"#));
+        assert!(output.contains(r#"try_from_env ("backend" , "BACKEND")"#));
+    }
+
+    #[test]
+    fn invalid_inputs_are_rejected() {
+        assert!(error(&parse_quote! { #[fig(key = "has-dash")] enum A { B } }).contains("key \"has-dash\" is invalid"));
+        assert!(error(&parse_quote! { enum Type { A } }).contains("keywords must be written as raw identifiers"));
+        assert!(error(&parse_quote! { struct A { #[fig(rename = "1st")] b: bool } }).contains("must not start"));
+        assert!(error(&parse_quote! { enum A {} }).contains("at least one variant"));
+        assert!(error(&parse_quote! { enum A { B(u8) } }).contains("only unit variants"));
+        assert!(
+            error(&parse_quote! { enum A { B, #[fig(rename = "b")] C } }).contains("duplicate configuration value")
+        );
+        assert!(error(&parse_quote! { enum A { #[fig(env = "B")] B } }).contains("expected one of: rename"));
+        assert!(error(&parse_quote! { #[fig(key = "a", key = "b")] enum A { B } }).contains("duplicate option"));
+        assert!(error(&parse_quote! { struct A(bool); }).contains("named fields"));
+        assert!(error(&parse_quote! { union A { b: u8 } }).contains("unions are not supported"));
+    }
+
+    #[test]
+    fn errors_point_at_the_attribute() {
+        let source = r#"struct A { #[fig(rename = "has-dash")] b: bool }"#;
+        let input = syn::parse_str::<DeriveInput>(source).expect("the input should parse");
+        let span = super::expand(&input).expect_err("the key should be rejected").span();
+
+        assert_eq!(span.start().column, source.find(r#""has-dash""#).expect("the key should be present"));
+        assert_eq!(span.end().column, span.start().column + r#""has-dash""#.len());
+    }
+}
@@ -0,0 +1,602 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements loading configuration schemas from TOML documents.
+//!
+//! Schemas may be read from a `fig.toml` file, or from the `[package.metadata.fig]` table of a package's `Cargo.toml`.
+//! Each table within a schema declares a configuration, using the table's name as its key:
+//!
+//! ```toml
+//! [backend]
+//! # One of `none`, `flag`, `any`, `one_of`, or `none_or_one_of`.
+//! # Defaults to `one_of` if `values` is provided, and `none` otherwise.
+//! kind = "one_of"
+//! values = ["epoll", "kqueue", "poll"]
+//! # The environment variable that the configuration is set from, if any.
+//! env = "BACKEND"
+//! # The value used if the environment variable is unset, or `true` to set the configuration without a value.
+//! default = "poll"
+//! ```
+//!
+//! Configurations without an environment variable or default value are declared but never set.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use toml::Spanned;
+use toml::de::{DeTable, DeValue};
+
+use crate::{Cfg, CheckedCfg, Directive, Error};
+
+/// The name of the file read by [`from_file`].
+pub const FILE_NAME: &str = "fig.toml";
+
+/// The name of the file read by [`from_manifest`].
+const MANIFEST_NAME: &str = "Cargo.toml";
+
+/// A TOML document that is being loaded.
+struct Source<'s> {
+    /// The path of the document.
+    path: &'s Path,
+    /// The contents of the document.
+    text: &'s str,
+}
+
+impl<'s> Source<'s> {
+    /// Reads the document at the given path, registering it as a dependency of the build script.
+    fn read(path: &'s Path, text: &'s mut String) -> Result<Self, Error> {
+        crate::sink::emit(Directive::RerunIfChanged { path: path.into() })?;
+
+        *text = std::fs::read_to_string(path).map_err(|source| Error::Read { path: path.into(), source })?;
+
+        Ok(Self { path, text })
+    }
+
+    /// Parses this document into a table.
+    fn parse(&self) -> Result<Spanned<DeTable<'s>>, Error> {
+        DeTable::parse(self.text).map_err(|error| self.error(error.span().unwrap_or_default(), error.message()))
+    }
+
+    /// Returns an error that points to the given span within this document.
+    fn error(&self, span: Range<usize>, message: impl Display) -> Error {
+        let before = &self.text[..span.start.min(self.text.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |line| line.chars().count()) + 1;
+
+        Error::Config { path: self.path.into(), line, column, message: message.to_string().into_boxed_str() }
+    }
+}
+
+/// Returns the directory containing the manifest of the package being built.
+pub(crate) fn manifest_dir() -> Result<PathBuf, Error> {
+    const VARIABLE: &str = "CARGO_MANIFEST_DIR";
+
+    crate::env::var(VARIABLE)?.map(PathBuf::from).ok_or_else(|| Error::MissingVariable { variable: VARIABLE.into() })
+}
+
+/// Loads the schema from the `fig.toml` file next to the package's `Cargo.toml`.
+///
+/// # Panics
+///
+/// This function will panic if the file could not be read, contains an invalid schema, or a configuration could not be
+/// declared or set.
+pub fn from_file() {
+    self::try_from_file().unwrap_or_else(|error| panic!("{error}"));
+}
+
+/// Loads the schema from the `fig.toml` file next to the package's `Cargo.toml`.
+///
+/// # Errors
+///
+/// This function will return an error if the file could not be read, contains an invalid schema, or a configuration
+/// could not be declared or set.
+pub fn try_from_file() -> Result<(), Error> {
+    self::try_load(self::manifest_dir()?.join(FILE_NAME))
+}
+
+/// Loads the schema from the `[package.metadata.fig]` table of the package's `Cargo.toml`.
+///
+/// Entries within the `[workspace.metadata.fig]` table of the workspace's `Cargo.toml` are inherited by every package,
+/// with each field of a package's entry taking precedence over the workspace's.
+///
+/// # Panics
+///
+/// This function will panic if a manifest could not be read, contains an invalid schema, or a configuration could not
+/// be declared or set.
+pub fn from_manifest() {
+    self::try_from_manifest().unwrap_or_else(|error| panic!("{error}"));
+}
+
+/// Loads the schema from the `[package.metadata.fig]` table of the package's `Cargo.toml`.
+///
+/// Entries within the `[workspace.metadata.fig]` table of the workspace's `Cargo.toml` are inherited by every package,
+/// with each field of a package's entry taking precedence over the workspace's.
+///
+/// # Errors
+///
+/// This function will return an error if a manifest could not be read, contains an invalid schema, or a configuration
+/// could not be declared or set.
+pub fn try_from_manifest() -> Result<(), Error> {
+    let manifest_dir = self::manifest_dir()?;
+    let package_path = manifest_dir.join(MANIFEST_NAME);
+    let mut package_text = String::new();
+    let package = Source::read(&package_path, &mut package_text)?;
+    let package_table = package.parse()?;
+
+    let workspace_path = if package_table.get_ref().contains_key("workspace") {
+        None
+    } else {
+        self::find_workspace_manifest(&manifest_dir)?
+    };
+    let mut workspace_text = String::new();
+    let workspace = workspace_path.as_deref().map(|path| Source::read(path, &mut workspace_text)).transpose()?;
+    let workspace_table = workspace.as_ref().map(Source::parse).transpose()?;
+    let (workspace, workspace_table) = match (&workspace, &workspace_table) {
+        (Some(source), Some(table)) => (source, table.get_ref()),
+        _ => (&package, package_table.get_ref()),
+    };
+
+    let mut entries = self::metadata(workspace, workspace_table, "workspace")?
+        .map(|table| self::parse_entries(workspace, table))
+        .transpose()?
+        .unwrap_or_default();
+
+    for (key, (location, entry)) in self::metadata(&package, package_table.get_ref(), "package")?
+        .map(|table| self::parse_entries(&package, table))
+        .transpose()?
+        .unwrap_or_default()
+    {
+        let entry = match entries.remove(key) {
+            Some((_, defaults)) => entry.inherit(defaults),
+            None => entry,
+        };
+
+        entries.insert(key, (location, entry));
+    }
+
+    entries.iter().try_for_each(|(key, (location, entry))| entry.apply(key, location))
+}
+
+/// Returns the path of the manifest that defines the workspace containing the given directory, if any.
+fn find_workspace_manifest(manifest_dir: &Path) -> Result<Option<PathBuf>, Error> {
+    for directory in manifest_dir.ancestors().skip(1) {
+        let path = directory.join(MANIFEST_NAME);
+        let Ok(text) = std::fs::read_to_string(&path) else { continue };
+        let source = Source { path: &path, text: &text };
+
+        if source.parse()?.get_ref().contains_key("workspace") {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Returns the `[<section>.metadata.fig]` table of the given manifest, if present.
+fn metadata<'a>(source: &Source<'_>, table: &'a DeTable<'a>, section: &str) -> Result<Option<&'a DeTable<'a>>, Error> {
+    let mut table = table;
+
+    for name in [section, "metadata", "fig"] {
+        match table.get(name).map(|value| (value.span(), value.get_ref())) {
+            Some((_, DeValue::Table(inner))) => table = inner,
+            Some((span, _)) => return Err(source.error(span, "expected a table")),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(table))
+}
+
+/// Loads the schema from the TOML file at the given path.
+///
+/// # Panics
+///
+/// This function will panic if the file could not be read, contains an invalid schema, or a configuration could not be
+/// declared or set.
+pub fn load(path: impl AsRef<Path>) {
+    self::try_load(path).unwrap_or_else(|error| panic!("{error}"));
+}
+
+/// Loads the schema from the TOML file at the given path.
+///
+/// # Errors
+///
+/// This function will return an error if the file could not be read, contains an invalid schema, or a configuration
+/// could not be declared or set.
+pub fn try_load(path: impl AsRef<Path>) -> Result<(), Error> {
+    let mut text = String::new();
+    let source = Source::read(path.as_ref(), &mut text)?;
+    let table = source.parse()?;
+
+    self::parse_entries(&source, table.get_ref())?
+        .iter()
+        .try_for_each(|(key, (location, entry))| entry.apply(key, location))
+}
+
+/// Parses every entry within the given table, keyed by the name of the configuration.
+fn parse_entries<'a>(
+    source: &'a Source<'a>,
+    table: &'a DeTable<'a>,
+) -> Result<BTreeMap<&'a str, (Location<'a>, Entry<'a>)>, Error> {
+    table
+        .iter()
+        .map(|(key, value)| {
+            let DeValue::Table(entry) = value.get_ref() else {
+                return Err(source.error(value.span(), "expected a table"));
+            };
+
+            Ok((&**key.get_ref(), (Location { source, span: key.span() }, Entry::parse(source, entry)?)))
+        })
+        .collect()
+}
+
+/// A location within a TOML document.
+#[derive(Clone)]
+struct Location<'a> {
+    /// The document.
+    source: &'a Source<'a>,
+    /// The byte range within the document.
+    span: Range<usize>,
+}
+
+impl Location<'_> {
+    /// Returns an error that points to this location.
+    fn error(&self, message: impl Display) -> Error {
+        self.source.error(self.span.clone(), message)
+    }
+}
+
+/// The value that a configuration is set to if its environment variable is unset.
+#[derive(Clone, Copy)]
+enum Fallback<'a> {
+    /// The configuration is not set.
+    Unset,
+    /// The configuration is set to the given value.
+    Set(Option<&'a str>),
+}
+
+/// A configuration described by a TOML table.
+#[derive(Clone, Default)]
+struct Entry<'a> {
+    /// The kind of configuration.
+    kind: Option<(Location<'a>, &'a str)>,
+    /// The values that the configuration may be assigned.
+    values: Option<(Location<'a>, Vec<&'a str>)>,
+    /// The environment variable that the configuration is set from.
+    variable: Option<&'a str>,
+    /// The value used if the environment variable is unset.
+    fallback: Option<(Location<'a>, Fallback<'a>)>,
+}
+
+impl<'a> Entry<'a> {
+    /// Parses an entry from the given table.
+    fn parse(source: &'a Source<'a>, table: &'a DeTable<'a>) -> Result<Self, Error> {
+        let mut entry = Self::default();
+
+        for (name, value) in table {
+            let location = Location { source, span: value.span() };
+
+            match &**name.get_ref() {
+                "kind" => entry.kind = Some((location, self::expect_str(source, value)?)),
+                "values" => entry.values = Some((location, self::expect_str_array(source, value)?)),
+                "env" => entry.variable = Some(self::expect_str(source, value)?),
+                "default" => {
+                    let fallback = match value.get_ref() {
+                        DeValue::String(value) => Fallback::Set(Some(value)),
+                        DeValue::Boolean(true) => Fallback::Set(None),
+                        DeValue::Boolean(false) => Fallback::Unset,
+                        _ => return Err(location.error("expected a string or boolean")),
+                    };
+
+                    entry.fallback = Some((location, fallback));
+                }
+                unknown => return Err(source.error(name.span(), format!("unknown field `{unknown}`"))),
+            }
+        }
+
+        Ok(entry)
+    }
+
+    /// Fills every field that is not set within this entry using the given defaults.
+    fn inherit(self, defaults: Self) -> Self {
+        Self {
+            kind: self.kind.or(defaults.kind),
+            values: self.values.or(defaults.values),
+            variable: self.variable.or(defaults.variable),
+            fallback: self.fallback.or(defaults.fallback),
+        }
+    }
+
+    /// Declares and sets the configuration described by this entry.
+    fn apply(&self, key: &str, location: &Location<'_>) -> Result<(), Error> {
+        let cfg = Cfg::try_new(key).map_err(|error| location.error(error))?;
+        let kind_location = self.kind.as_ref().map_or(location, |(location, _)| location);
+        let kind = self.kind.as_ref().map_or_else(|| if self.values.is_some() { "one_of" } else { "none" }, |v| v.1);
+        let declared = |error| location.error(error);
+
+        match (kind, self.values.as_ref().map(|(_, values)| &**values)) {
+            ("none", None) => self.set(&cfg.try_assigned_none().map_err(declared)?),
+            ("flag", None) => self.set(&cfg.try_flag().map_err(declared)?),
+            ("any", None) => self.set(&cfg.try_assigned_any().map_err(declared)?),
+            ("one_of", Some(values)) => self.set(&cfg.try_assigned_one_of(values).map_err(declared)?),
+            ("none_or_one_of", Some(values)) => self.set(&cfg.try_assigned_none_or_one_of(values).map_err(declared)?),
+            ("none" | "flag" | "any", Some(_)) => {
+                Err(kind_location.error(format!("kind `{kind}` does not accept `values`")))
+            }
+            ("one_of" | "none_or_one_of", None) => Err(kind_location.error(format!("kind `{kind}` requires `values`"))),
+            _ => Err(kind_location.error(format!("unknown kind `{kind}`"))),
+        }
+    }
+
+    /// Sets the given configuration from this entry's environment variable, falling back to its default value.
+    fn set<'i>(&self, cfg: &impl CheckedCfg<'i>) -> Result<(), Error> {
+        if let Some(variable) = self.variable {
+            if let Some(value) = crate::env::read(variable)? {
+                return cfg.try_set_from_env_value(Some(&value)).map_err(|error| error.in_variable(variable));
+            }
+        }
+
+        match &self.fallback {
+            Some((location, Fallback::Set(value))) => cfg.try_set(*value).map_err(|error| location.error(error)),
+            Some((_, Fallback::Unset)) | None => Ok(()),
+        }
+    }
+}
+
+/// Returns the given value as a string, or an error if it is not a string.
+fn expect_str<'v>(source: &Source<'_>, value: &'v Spanned<DeValue<'_>>) -> Result<&'v str, Error> {
+    match value.get_ref() {
+        DeValue::String(string) => Ok(string),
+        _ => Err(source.error(value.span(), "expected a string")),
+    }
+}
+
+/// Returns the given value as an array of strings, or an error if it is not an array of strings.
+fn expect_str_array<'v>(source: &Source<'_>, value: &'v Spanned<DeValue<'_>>) -> Result<Vec<&'v str>, Error> {
+    match value.get_ref() {
+        DeValue::Array(array) => array.iter().map(|value| self::expect_str(source, value)).collect(),
+        _ => Err(source.error(value.span(), "expected an array of strings")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::directive::Values;
+    use crate::testing::Harness;
+    use crate::{Directive, Error};
+
+    /// A temporary directory that is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        /// Creates a new empty temporary directory.
+        fn new() -> Self {
+            /// The number of directories that have been created, used to give each one a unique name.
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+            let name = format!("fig-config-test-{}-{}", std::process::id(), COUNT.fetch_add(1, Ordering::Relaxed));
+            let path = std::env::temp_dir().join(name);
+
+            std::fs::create_dir_all(&path).unwrap_or_else(|error| panic!("{error}"));
+
+            Self(path)
+        }
+
+        /// Writes the given file within this directory, creating its parent directories, and returns its path.
+        fn write(&self, name: &str, text: &str) -> PathBuf {
+            let path = self.0.join(name);
+
+            std::fs::create_dir_all(path.parent().unwrap_or(&self.0)).unwrap_or_else(|error| panic!("{error}"));
+            std::fs::write(&path, text).unwrap_or_else(|error| panic!("{error}"));
+
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            drop(std::fs::remove_dir_all(&self.0));
+        }
+    }
+
+    /// Returns a list of values from the given strings.
+    fn list(values: &[&str]) -> Box<[Box<str>]> {
+        values.iter().map(|&v| v.into()).collect()
+    }
+
+    /// Loads the given `fig.toml` document with the given environment variables.
+    fn load(text: &str, variables: &[(&str, &str)]) -> (Result<(), Error>, Vec<Directive>, PathBuf) {
+        let directory = TempDir::new();
+        let path = directory.write(super::FILE_NAME, text);
+        let harness = Harness::new().env("CARGO_MANIFEST_DIR", &directory.0).envs(variables.iter().copied());
+        let (result, directives) = harness.run(super::try_from_file);
+
+        (result, directives, path)
+    }
+
+    /// Returns the line, column, and message of the error produced by loading the given `fig.toml` document.
+    fn error(text: &str) -> (usize, usize, String) {
+        match load(text, &[]).0 {
+            Err(Error::Config { line, column, message, .. }) => (line, column, message.into_string()),
+            other => panic!("expected a configuration error, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn files_are_declared_set_and_registered() {
+        let text = r#"
+            [backend]
+            values = ["epoll", "poll"]
+            env = "BACKEND"
+            default = "poll"
+
+            [simd]
+            kind = "flag"
+            env = "SIMD"
+
+            [unused]
+        "#;
+        let (result, directives, path) = load(text, &[("SIMD", "on")]);
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            directives,
+            [
+                Directive::RerunIfChanged { path: path.into() },
+                Directive::CheckCfg { key: "backend".into(), values: Values::OneOf(list(&["epoll", "poll"])) },
+                Directive::RerunIfEnvChanged { variable: "BACKEND".into() },
+                Directive::Cfg { key: "backend".into(), value: Some("poll".into()) },
+                Directive::CheckCfg { key: "simd".into(), values: Values::None },
+                Directive::RerunIfEnvChanged { variable: "SIMD".into() },
+                Directive::Cfg { key: "simd".into(), value: None },
+                Directive::CheckCfg { key: "unused".into(), values: Values::None },
+            ]
+        );
+    }
+
+    #[test]
+    fn kinds_and_values_are_validated() {
+        assert_eq!(error("[a]\nkind = \"one_of\""), (2, 8, "kind `one_of` requires `values`".into()));
+        assert_eq!(error("[a]\nkind = \"any\"\nvalues = []"), (2, 8, "kind `any` does not accept `values`".into()));
+        assert_eq!(error("[a]\nkind = \"some\""), (2, 8, "unknown kind `some`".into()));
+        assert_eq!(error("[a]\nvalues = []").2, "at least one value should be provided for configuration 'a'");
+        assert_eq!(error("[a]\nvalues = [1]"), (2, 11, "expected a string".into()));
+        assert_eq!(error("[a]\ndefault = 1"), (2, 11, "expected a string or boolean".into()));
+        assert_eq!(error("[a]\ncolour = \"red\""), (2, 1, "unknown field `colour`".into()));
+        assert_eq!(error("a = 1"), (1, 5, "expected a table".into()));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_line_and_column() {
+        let (line, column, message) = error("\n\n[\"has-dash\"]");
+
+        assert_eq!((line, column), (3, 2));
+        assert!(message.starts_with(r#"configuration key "has-dash" is invalid"#), "{message}");
+        assert_eq!(error("[a]\nvalues = [\"x\"]\n  default = true").0, 3);
+        // Columns are counted in characters rather than bytes.
+        assert_eq!(error("[a]\nvalues = [\"☃\", 1]"), (2, 16, "expected a string".into()));
+        assert_eq!(error("[a]\nvalues = [\"x\"]\ndefault = \"y\"").0, 3);
+
+        let (line, column, _) = error("[a\nb");
+
+        // Syntax errors are mapped in the same way.
+        assert_eq!((line, column), (1, 3));
+    }
+
+    #[test]
+    fn invalid_values_name_their_variable() {
+        let (result, ..) = load("[a]\nvalues = [\"x\"]\nenv = \"A\"", &[("A", "y")]);
+
+        assert!(matches!(result, Err(Error::Variable { variable, .. }) if &*variable == "A"));
+    }
+
+    #[test]
+    fn missing_files_are_reported() {
+        let directory = TempDir::new();
+        let harness = Harness::new().env("CARGO_MANIFEST_DIR", &directory.0);
+
+        assert!(matches!(harness.run(super::try_from_file).0, Err(Error::Read { .. })));
+        assert!(!Path::new(&directory.0.join(super::FILE_NAME)).exists());
+    }
+
+    /// Loads the manifest of the package within the given directory.
+    fn load_manifest(directory: &Path, variables: &[(&str, &str)]) -> (Result<(), Error>, Vec<Directive>) {
+        let harness = Harness::new().env("CARGO_MANIFEST_DIR", directory).envs(variables.iter().copied());
+
+        harness.run(super::try_from_manifest)
+    }
+
+    #[test]
+    fn packages_inherit_workspace_entries_field_by_field() {
+        let directory = TempDir::new();
+        let workspace = directory.write(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["member"]
+
+            [workspace.metadata.fig.backend]
+            values = ["epoll", "poll"]
+            env = "BACKEND"
+            default = "epoll"
+
+            [workspace.metadata.fig.shared]
+            kind = "flag"
+            default = true
+        "#,
+        );
+        let package = directory.write(
+            "member/Cargo.toml",
+            r#"
+            [package]
+            name = "member"
+
+            [package.metadata.fig.backend]
+            default = "poll"
+
+            [package.metadata.fig.local]
+        "#,
+        );
+        let (result, directives) = load_manifest(&directory.0.join("member"), &[]);
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            directives,
+            [
+                Directive::RerunIfChanged { path: package.into() },
+                Directive::RerunIfChanged { path: workspace.into() },
+                Directive::CheckCfg { key: "backend".into(), values: Values::OneOf(list(&["epoll", "poll"])) },
+                Directive::RerunIfEnvChanged { variable: "BACKEND".into() },
+                Directive::Cfg { key: "backend".into(), value: Some("poll".into()) },
+                Directive::CheckCfg { key: "local".into(), values: Values::None },
+                Directive::CheckCfg { key: "shared".into(), values: Values::None },
+                Directive::Cfg { key: "shared".into(), value: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn packages_without_a_workspace_are_loaded_alone() {
+        let directory = TempDir::new();
+
+        directory
+            .write("package/Cargo.toml", "[package]\nname = \"package\"\n\n[package.metadata.fig.a]\nvalues = [\"x\"]");
+
+        let (result, directives) = load_manifest(&directory.0.join("package"), &[]);
+        let checks = directives.iter().filter(|directive| matches!(directive, Directive::CheckCfg { .. })).count();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(checks, 1);
+    }
+
+    #[test]
+    fn workspace_errors_point_at_the_workspace_manifest() {
+        let directory = TempDir::new();
+        let workspace = directory.write("Cargo.toml", "[workspace]\n\n[workspace.metadata.fig.a]\nkind = \"some\"");
+
+        directory.write("member/Cargo.toml", "[package]\nname = \"member\"");
+
+        match load_manifest(&directory.0.join("member"), &[]).0 {
+            Err(Error::Config { path, line, column, .. }) => assert_eq!((&*path, line, column), (&*workspace, 4, 8)),
+            other => panic!("expected a configuration error, found {other:?}"),
+        }
+    }
+}
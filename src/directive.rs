@@ -0,0 +1,277 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Defines the build script directives that are emitted by this crate.
+
+use std::fmt::{Display, Write};
+use std::path::Path;
+
+use crate::Error;
+
+/// Appends the given value to the string as an escaped Rust string literal.
+fn push_str_literal(string: &mut String, value: &str) {
+    string.reserve(value.len() + 2);
+    string.push('"');
+
+    for character in value.chars() {
+        match character {
+            '"' => string.push_str(r#"\""#),
+            '\\' => string.push_str(r"\\"),
+            '\n' => string.push_str(r"\n"),
+            '\r' => string.push_str(r"\r"),
+            '\t' => string.push_str(r"\t"),
+            '\0' => string.push_str(r"\0"),
+            // Writing into a `String` never fails.
+            c if c.is_control() => drop(write!(string, r"\u{{{:x}}}", u32::from(c))),
+            c => string.push(c),
+        }
+    }
+
+    string.push('"');
+}
+
+/// Converts the given value into an escaped Rust string literal.
+pub(crate) fn to_str_literal(value: &str) -> Box<str> {
+    let mut string = String::new();
+
+    self::push_str_literal(&mut string, value);
+
+    string.into_boxed_str()
+}
+
+/// Converts the given list of strings into a valid value string.
+pub(crate) fn list_to_value_str(values: &[Box<str>]) -> Box<str> {
+    const SEPARATOR: &str = ", ";
+
+    let final_index = values.len().saturating_sub(1);
+    let initial_capcity = values.iter().map(|s| s.len() + 2).sum::<usize>() + (SEPARATOR.len() * final_index);
+    let mut value_string = String::with_capacity(initial_capcity);
+
+    for (index, value) in values.iter().enumerate() {
+        self::push_str_literal(&mut value_string, value);
+
+        if index != final_index {
+            value_string += SEPARATOR;
+        }
+    }
+
+    value_string.into_boxed_str()
+}
+
+/// A single instruction sent to Cargo by a build script.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Directive {
+    /// Registers a configuration and the values that it may be assigned.
+    CheckCfg {
+        /// The configuration key.
+        key: Box<str>,
+        /// The values that the configuration may be assigned.
+        values: Values,
+    },
+    /// Sets a configuration for the current build.
+    Cfg {
+        /// The configuration key.
+        key: Box<str>,
+        /// The assigned value, if any.
+        value: Option<Box<str>>,
+    },
+    /// Tells Cargo to re-run the build script if the given environment variable changes.
+    RerunIfEnvChanged {
+        /// The environment variable key.
+        variable: Box<str>,
+    },
+    /// Tells Cargo to re-run the build script if the given file changes.
+    RerunIfChanged {
+        /// The file path.
+        path: Box<Path>,
+    },
+}
+
+impl Directive {
+    /// Validates that this directive can be written on a single line.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        let has_line_break = |bytes: &[u8]| bytes.contains(&b'\n') || bytes.contains(&b'\r');
+
+        match self {
+            Self::RerunIfEnvChanged { variable } if has_line_break(variable.as_bytes()) => {
+                Err(Error::InvalidVariableKey { variable: variable.clone() })
+            }
+            Self::RerunIfChanged { path } if has_line_break(path.as_os_str().as_encoded_bytes()) => {
+                Err(Error::InvalidPath { path: path.clone() })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Display for Directive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CheckCfg { key, values } => write!(f, "cargo::rustc-check-cfg=cfg({key}, values({values}))"),
+            Self::Cfg { key, value: Some(value) } => {
+                write!(f, "cargo::rustc-cfg={key}={}", self::to_str_literal(value))
+            }
+            Self::Cfg { key, value: None } => write!(f, "cargo::rustc-cfg={key}"),
+            Self::RerunIfEnvChanged { variable } => write!(f, "cargo::rerun-if-env-changed={variable}"),
+            Self::RerunIfChanged { path } => write!(f, "cargo::rerun-if-changed={}", path.display()),
+        }
+    }
+}
+
+/// The values that a configuration may be assigned.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Values {
+    /// The configuration may not be assigned a value.
+    None,
+    /// The configuration may be assigned any value.
+    Any,
+    /// The configuration must be assigned one of the listed values.
+    OneOf(Box<[Box<str>]>),
+    /// The configuration may be assigned no value or one of the listed values.
+    NoneOrOneOf(Box<[Box<str>]>),
+    /// The configuration may be assigned any number of the listed values at once.
+    AnyOf(Box<[Box<str>]>),
+}
+
+impl Display for Values {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("none()"),
+            Self::Any => f.write_str("any()"),
+            Self::OneOf(values) | Self::AnyOf(values) => f.write_str(&self::list_to_value_str(values)),
+            Self::NoneOrOneOf(values) => write!(f, "none(), {}", self::list_to_value_str(values)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Directive, Values};
+    use crate::Error;
+    use crate::sink::{Sink, Writer};
+
+    /// Values that exercise every escape written by [`super::push_str_literal`].
+    const VALUES: &[&str] = &[
+        "",
+        "plain",
+        "quote \" inside",
+        r"back\slash",
+        "new\nline",
+        "carriage\rreturn",
+        "tab\tbed",
+        "nul\0byte",
+        "bell\u{7}and\u{1b}escape",
+        "delete\u{7f}",
+        "unicode \u{2028} snowman ☃",
+        "\"\\\n",
+    ];
+
+    /// Parses a single string literal from the start of the given source, returning it and the remaining source.
+    fn parse_literal(source: &str) -> (String, &str) {
+        let mut characters = source.strip_prefix('"').expect("the literal should start with a quote").char_indices();
+        let mut value = String::new();
+
+        while let Some((index, character)) = characters.next() {
+            match character {
+                '"' => return (value, &source[index + 2..]),
+                '\\' => match characters.next().expect("the escape should be complete").1 {
+                    '"' => value.push('"'),
+                    '\\' => value.push('\\'),
+                    'n' => value.push('\n'),
+                    'r' => value.push('\r'),
+                    't' => value.push('\t'),
+                    '0' => value.push('\0'),
+                    'u' => {
+                        let digits: String = characters.by_ref().map(|(_, c)| c).take_while(|&c| c != '}').collect();
+                        let digits = digits.strip_prefix('{').expect("the escape should be braced");
+                        let code = u32::from_str_radix(digits, 16).expect("the escape should be hexadecimal");
+
+                        value.push(char::from_u32(code).expect("the escape should be a valid character"));
+                    }
+                    other => panic!("unexpected escape {other:?}"),
+                },
+                character => value.push(character),
+            }
+        }
+
+        panic!("the literal should be terminated")
+    }
+
+    #[test]
+    fn cfg_values_round_trip() {
+        for &value in VALUES {
+            let line = Directive::Cfg { key: "key".into(), value: Some(value.into()) }.to_string();
+            let literal = line.strip_prefix("cargo::rustc-cfg=key=").expect("the directive should be a cfg");
+
+            assert!(!literal.contains(['\n', '\r']), "{line:?} spans several lines");
+            assert_eq!(parse_literal(literal), (value.to_owned(), ""));
+        }
+    }
+
+    #[test]
+    fn check_cfg_values_round_trip() {
+        let values = Values::OneOf(VALUES.iter().map(|&v| v.into()).collect());
+        let line = Directive::CheckCfg { key: "key".into(), values }.to_string();
+        let mut list =
+            line.strip_prefix("cargo::rustc-check-cfg=cfg(key, values(").expect("the directive should check");
+        let mut parsed = Vec::new();
+
+        assert!(!line.contains(['\n', '\r']), "{line:?} spans several lines");
+
+        loop {
+            let (value, rest) = parse_literal(list);
+
+            parsed.push(value);
+
+            match rest.strip_prefix(", ") {
+                Some(rest) => list = rest,
+                None => break assert_eq!(rest, "))"),
+            }
+        }
+
+        assert_eq!(parsed, VALUES);
+    }
+
+    #[test]
+    fn none_or_one_of_lists_none_first() {
+        let values = Values::NoneOrOneOf(vec!["a".into(), "b".into()].into_boxed_slice());
+
+        assert_eq!(values.to_string(), r#"none(), "a", "b""#);
+    }
+
+    #[test]
+    fn line_breaks_are_rejected() {
+        let mut writer = Writer::new(Vec::new());
+        let variable = Directive::RerunIfEnvChanged { variable: "FOO\ncargo::warning=hi".into() };
+        let path = Directive::RerunIfChanged { path: Path::new("a\r\nb").into() };
+
+        assert!(matches!(writer.emit(variable), Err(Error::InvalidVariableKey { .. })));
+        assert!(matches!(writer.emit(path), Err(Error::InvalidPath { .. })));
+        assert!(writer.get_ref().is_empty());
+    }
+
+    #[test]
+    fn variable_keys_with_line_breaks_are_not_read() {
+        let (result, writer) =
+            crate::sink::with_sink(Writer::new(Vec::new()), || crate::env::read("FOO\ncargo::warning=hi"));
+
+        assert!(matches!(result, Err(Error::InvalidVariableKey { .. })));
+        assert!(writer.get_ref().is_empty());
+    }
+}
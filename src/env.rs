@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements access to environment variables that may be replaced on the current thread.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use crate::{Directive, Error};
+
+thread_local! {
+    /// The environment used by the current thread, or `None` if the process environment should be used.
+    static VARIABLES: RefCell<Option<BTreeMap<OsString, OsString>>> = const { RefCell::new(None) };
+}
+
+/// Returns the value of the given environment variable, if it is present.
+pub fn var_os(key: &str) -> Option<OsString> {
+    VARIABLES.with_borrow(|variables| {
+        variables.as_ref().map_or_else(|| std::env::var_os(key), |v| v.get(std::ffi::OsStr::new(key)).cloned())
+    })
+}
+
+/// Returns every environment variable whose key starts with the given prefix, sorted by key.
+pub fn vars_with_prefix(prefix: &str) -> Vec<(OsString, OsString)> {
+    let matches = |key: &OsString| key.to_str().is_some_and(|key| key.starts_with(prefix));
+    let mut variables: Vec<_> = VARIABLES.with_borrow(|variables| {
+        variables.as_ref().map_or_else(
+            || std::env::vars_os().filter(|(k, _)| matches(k)).collect(),
+            |variables| variables.iter().filter(|(k, _)| matches(k)).map(|(k, v)| (k.clone(), v.clone())).collect(),
+        )
+    });
+
+    variables.sort_unstable();
+    variables
+}
+
+/// Reads the given environment variable, registering it as a dependency of the build script.
+///
+/// Variables that are not present or are empty are treated as unset.
+pub fn read(key: &str) -> Result<Option<String>, Error> {
+    self::validate(key)?;
+
+    crate::sink::emit(Directive::RerunIfEnvChanged { variable: key.into() })?;
+
+    self::var(key)
+}
+
+/// Returns the value of the given environment variable without registering it as a dependency of the build script.
+///
+/// Variables that are not present or are empty are treated as unset.
+pub fn var(key: &str) -> Result<Option<String>, Error> {
+    self::validate(key)?;
+
+    match self::var_os(key).map(OsString::into_string) {
+        Some(Ok(value)) if !value.is_empty() => Ok(Some(value)),
+        Some(Ok(_)) | None => Ok(None),
+        Some(Err(_)) => Err(Error::NonUnicodeVariable { variable: key.into() }),
+    }
+}
+
+/// The values accepted by [`parse_flag`], listing those that set a flag before those that leave it unset.
+pub const FLAG_VALUES: [&str; 8] = ["1", "true", "yes", "on", "0", "false", "no", "off"];
+
+/// Interprets the given value as a boolean, ignoring case and surrounding whitespace.
+///
+/// Returns `None` if the value is not one of `1`, `true`, `yes`, `on`, `0`, `false`, `no`, or `off`.
+pub fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the given Cargo feature is enabled for the package being built.
+pub fn has_feature(feature: &str) -> bool {
+    self::var_os(&format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"))).is_some()
+}
+
+/// Validates that the given environment variable key may be read.
+fn validate(key: &str) -> Result<(), Error> {
+    if key.is_empty() || key.contains(['=', '\0', '\n', '\r']) {
+        Err(Error::InvalidVariableKey { variable: key.into() })
+    } else {
+        Ok(())
+    }
+}
+
+/// Replaces the current thread's environment with the given variables while running the given function.
+///
+/// The previous environment is restored once the function returns, even if it panics.
+pub fn with_vars<F, T>(variables: BTreeMap<OsString, OsString>, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    /// Restores the previous environment when dropped.
+    struct Guard(Option<BTreeMap<OsString, OsString>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            VARIABLES.set(self.0.take());
+
+            crate::Target::forget_current();
+        }
+    }
+
+    let _guard = Guard(VARIABLES.replace(Some(variables)));
+
+    crate::Target::forget_current();
+
+    f()
+}
@@ -0,0 +1,415 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Defines the error type returned by the fallible parts of the API.
+
+use std::fmt::Display;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::directive::Values;
+
+/// An error that may occur while declaring or setting a configuration.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// A configuration key was not a valid identifier.
+    InvalidKey {
+        /// The configuration key.
+        key: Box<str>,
+        /// The reason that the key was rejected.
+        reason: InvalidKeyReason,
+    },
+    /// A configuration was declared with an empty list of values.
+    EmptyValues {
+        /// The configuration key.
+        key: Box<str>,
+    },
+    /// A value could not be assigned to a configuration.
+    Unassignable {
+        /// The configuration key.
+        key: Box<str>,
+        /// The value that was rejected.
+        value: Option<Box<str>>,
+        /// The values that the configuration accepts, or an empty list if they cannot be listed.
+        allowed: Box<[Box<str>]>,
+    },
+    /// A value read from an environment variable could not be assigned to a configuration.
+    Variable {
+        /// The environment variable key.
+        variable: Box<str>,
+        /// The error caused by the value.
+        source: Box<Self>,
+    },
+    /// A value was rejected by a configuration's validator.
+    Rejected {
+        /// The configuration key.
+        key: Box<str>,
+        /// The value that was rejected.
+        value: Box<str>,
+        /// The reason that the value was rejected.
+        reason: Box<str>,
+    },
+    /// A glob pattern could not be compiled.
+    InvalidPattern {
+        /// The pattern source.
+        pattern: Box<str>,
+        /// A description of the problem.
+        message: Box<str>,
+    },
+    /// A value could not be parsed as an integer.
+    InvalidInteger {
+        /// The configuration key.
+        key: Box<str>,
+        /// The value that was rejected.
+        value: Box<str>,
+    },
+    /// An integer was outside of the range that a configuration accepts.
+    IntegerOutOfRange {
+        /// The configuration key.
+        key: Box<str>,
+        /// The value that was rejected.
+        value: Box<str>,
+        /// The smallest accepted integer.
+        start: i64,
+        /// The largest accepted integer.
+        end: i64,
+    },
+    /// A configuration was declared with a range of integers that is too large to be listed.
+    RangeTooLarge {
+        /// The configuration key.
+        key: Box<str>,
+        /// The smallest integer within the range.
+        start: i64,
+        /// The largest integer within the range.
+        end: i64,
+        /// The largest number of integers that a range may contain.
+        limit: usize,
+    },
+    /// An environment variable contained a value that was not valid unicode.
+    NonUnicodeVariable {
+        /// The environment variable key.
+        variable: Box<str>,
+    },
+    /// Several mutually exclusive Cargo features were enabled at once.
+    ConflictingFeatures {
+        /// The configuration key.
+        key: Box<str>,
+        /// The enabled features.
+        features: Box<[Box<str>]>,
+    },
+    /// A required environment variable was not present.
+    MissingVariable {
+        /// The environment variable key.
+        variable: Box<str>,
+    },
+    /// An environment variable key was empty or contained an invalid character.
+    InvalidVariableKey {
+        /// The environment variable key.
+        variable: Box<str>,
+    },
+    /// A file path contained a line break, which cannot be written within a directive.
+    InvalidPath {
+        /// The file path.
+        path: Box<Path>,
+    },
+    /// A configuration was declared twice with incompatible values.
+    ConflictingDeclaration {
+        /// The configuration key.
+        key: Box<str>,
+        /// The values that were previously declared.
+        previous: Values,
+        /// The values that were rejected.
+        values: Values,
+    },
+    /// A configuration was assigned twice with different values.
+    ConflictingAssignment {
+        /// The configuration key.
+        key: Box<str>,
+        /// The value that was previously assigned.
+        previous: Option<Box<str>>,
+        /// The value that was rejected.
+        value: Option<Box<str>>,
+    },
+    /// A file could not be read.
+    Read {
+        /// The file path.
+        path: Box<Path>,
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+    /// A configuration file contained an invalid schema.
+    Config {
+        /// The file path.
+        path: Box<Path>,
+        /// The line number of the offending value, starting from one.
+        line: usize,
+        /// The column number of the offending value, starting from one.
+        column: usize,
+        /// A description of the problem.
+        message: Box<str>,
+    },
+    /// A predicate could not be parsed.
+    InvalidPredicate {
+        /// The predicate source.
+        predicate: Box<str>,
+        /// The byte range of the offending token.
+        span: Range<usize>,
+        /// A description of the problem.
+        message: Box<str>,
+    },
+    /// An external command could not be run, or did not succeed.
+    Command {
+        /// The program that was run.
+        program: Box<str>,
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+    /// A version string could not be parsed.
+    InvalidVersion {
+        /// The version string.
+        version: Box<str>,
+    },
+    /// A directive could not be written to its sink.
+    Output {
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey { key, reason } => write!(f, "configuration key {key:?} is invalid: {reason}"),
+            Self::EmptyValues { key } => write!(f, "at least one value should be provided for configuration '{key}'"),
+            Self::Unassignable { key, value, allowed } => {
+                match value {
+                    Some(value) => write!(f, "{} is not assignable", crate::directive::to_str_literal(value))?,
+                    None => f.write_str("no value is not assignable")?,
+                }
+
+                write!(f, " to configuration '{key}'")?;
+
+                if !allowed.is_empty() {
+                    write!(f, "; expected one of {}", crate::directive::list_to_value_str(allowed))?;
+                }
+
+                if let Some(suggestion) = value.as_deref().and_then(|value| self::suggest(value, allowed)) {
+                    write!(f, " (did you mean {}?)", crate::directive::to_str_literal(suggestion))?;
+                }
+
+                Ok(())
+            }
+            Self::Variable { variable, .. } => write!(f, "invalid value in environment variable '{variable}'"),
+            Self::Rejected { key, value, reason } => {
+                write!(f, "{value:?} is not assignable to configuration '{key}': {reason}")
+            }
+            Self::InvalidPattern { pattern, message } => write!(f, "invalid pattern `{pattern}`: {message}"),
+            Self::InvalidInteger { key, value } => {
+                write!(f, "{value:?} is not an integer, as required by configuration '{key}'")
+            }
+            Self::IntegerOutOfRange { key, value, start, end } => {
+                write!(f, "{value} is outside of the range {start}..={end} accepted by configuration '{key}'")
+            }
+            Self::RangeTooLarge { key, start, end, limit } => {
+                write!(f, "the range {start}..={end} of configuration '{key}' contains more than {limit} integers")
+            }
+            Self::NonUnicodeVariable { variable } => {
+                write!(f, "environment variable '{variable}' does not contain valid unicode")
+            }
+            Self::ConflictingFeatures { key, features } => {
+                write!(
+                    f,
+                    "features `{}` may not be enabled together, as they each set configuration '{key}'",
+                    features.join("`, `")
+                )
+            }
+            Self::MissingVariable { variable } => write!(f, "environment variable '{variable}' is not present"),
+            Self::InvalidVariableKey { variable } => write!(f, "environment variable key {variable:?} is invalid"),
+            Self::InvalidPath { path } => write!(f, "path {:?} may not contain a line break", path.to_string_lossy()),
+            Self::ConflictingDeclaration { key, previous, values } => {
+                write!(f, "configuration '{key}' was declared with `values({previous})` and `values({values})`")
+            }
+            Self::ConflictingAssignment { key, previous, value } => {
+                write!(f, "configuration '{key}' was assigned `{:?}` and `{:?}`", previous.as_deref(), value.as_deref())
+            }
+            Self::Read { path, source } => write!(f, "failed to read '{}': {source}", path.display()),
+            Self::Config { path, line, column, message } => {
+                write!(f, "invalid configuration at {}:{line}:{column}: {message}", path.display())
+            }
+            Self::InvalidPredicate { predicate, span, message } => {
+                write!(f, "invalid predicate `{predicate}` at {}..{}: {message}", span.start, span.end)
+            }
+            Self::Command { program, source } => write!(f, "failed to run '{program}': {source}"),
+            Self::InvalidVersion { version } => write!(f, "version {version:?} could not be parsed"),
+            Self::Output { source } => write!(f, "failed to emit directive: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read { source, .. } | Self::Command { source, .. } | Self::Output { source } => Some(source),
+            Self::Variable { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// Records that the value that caused this error was read from the given environment variable.
+    pub(crate) fn in_variable(self, variable: &str) -> Self {
+        Self::Variable { variable: variable.into(), source: Box::new(self) }
+    }
+}
+
+/// Returns the allowed value that is closest to the given value, if any are close enough to be a likely typo.
+fn suggest<'a>(value: &str, allowed: &'a [Box<str>]) -> Option<&'a str> {
+    let threshold = value.chars().count().max(3) / 3;
+
+    allowed
+        .iter()
+        .map(|candidate| (self::edit_distance(value, candidate), candidate))
+        .filter(|&(distance, _)| distance <= threshold)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| &**candidate)
+}
+
+/// Returns the number of single-character insertions, deletions, and substitutions needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a) in a.chars().enumerate() {
+        current[0] = i + 1;
+
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// The reason that a configuration key was rejected.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidKeyReason {
+    /// The key was empty.
+    Empty,
+    /// The key was a lone underscore.
+    Underscore,
+    /// The key started with a character that may not start an identifier.
+    InvalidStart(char),
+    /// The key contained a character that may not appear within an identifier.
+    InvalidCharacter(char),
+    /// The key was a keyword, and should be written as a raw identifier instead.
+    Keyword,
+    /// The key was a raw identifier of a keyword that does not support raw identifiers.
+    RawKeyword,
+}
+
+impl Display for InvalidKeyReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("identifiers must not be empty"),
+            Self::Underscore => f.write_str("`_` is not a valid identifier"),
+            Self::InvalidStart(c) => write!(f, "identifiers must not start with {c:?}"),
+            Self::InvalidCharacter(c) => write!(f, "identifiers must not contain {c:?}"),
+            Self::Keyword => f.write_str("keywords must be written as raw identifiers"),
+            Self::RawKeyword => f.write_str("this keyword cannot be a raw identifier"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    /// Returns a list of values from the given strings.
+    fn list(values: &[&str]) -> Box<[Box<str>]> {
+        values.iter().map(|&v| v.into()).collect()
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(super::edit_distance("", ""), 0);
+        assert_eq!(super::edit_distance("", "abc"), 3);
+        assert_eq!(super::edit_distance("abc", ""), 3);
+        assert_eq!(super::edit_distance("epoll", "epoll"), 0);
+        assert_eq!(super::edit_distance("epol", "epoll"), 1);
+        assert_eq!(super::edit_distance("epoll", "epol"), 1);
+        assert_eq!(super::edit_distance("epoxl", "epoll"), 1);
+        assert_eq!(super::edit_distance("kitten", "sitting"), 3);
+        assert_eq!(super::edit_distance("☃x", "☃y"), 1);
+    }
+
+    #[test]
+    fn suggestions_stay_within_the_threshold() {
+        let allowed = list(&["epoll", "kqueue", "iocp"]);
+
+        // Values of up to five characters allow a single edit, and each further three characters allow another.
+        assert_eq!(super::suggest("epol", &allowed), Some("epoll"));
+        assert_eq!(super::suggest("eplol", &allowed), None);
+        assert_eq!(super::suggest("ep", &allowed), None);
+        assert_eq!(super::suggest("kqeueu", &allowed), Some("kqueue"));
+        assert_eq!(super::suggest("select", &allowed), None);
+        assert_eq!(super::suggest("x", &list(&["y"])), Some("y"));
+        assert_eq!(super::suggest("epoll", &[]), None);
+    }
+
+    #[test]
+    fn suggestions_prefer_the_closest_value() {
+        assert_eq!(super::suggest("abcd", &list(&["abxy", "abcx"])), Some("abcx"));
+        assert_eq!(super::suggest("abcdefgh", &list(&["abcdefxy", "abcdefgx"])), Some("abcdefgx"));
+    }
+
+    #[test]
+    fn unassignable_values_are_quoted() {
+        let error = |value: Option<&str>, allowed: &[&str]| {
+            Error::Unassignable { key: "backend".into(), value: value.map(Into::into), allowed: list(allowed) }
+                .to_string()
+        };
+
+        assert_eq!(
+            error(Some("epol"), &["epoll", "kqueue"]),
+            r#""epol" is not assignable to configuration 'backend'; expected one of "epoll", "kqueue" (did you mean "epoll"?)"#
+        );
+        assert_eq!(error(Some("a\"b\n"), &[]), r#""a\"b\n" is not assignable to configuration 'backend'"#);
+        assert_eq!(
+            error(None, &["epoll"]),
+            r#"no value is not assignable to configuration 'backend'; expected one of "epoll""#
+        );
+    }
+
+    #[test]
+    fn variables_leave_their_cause_to_the_source() {
+        let cause = Error::Unassignable { key: "backend".into(), value: Some("epol".into()), allowed: list(&[]) };
+        let error = cause.in_variable("BACKEND");
+
+        assert_eq!(error.to_string(), "invalid value in environment variable 'BACKEND'");
+        assert_eq!(
+            std::error::Error::source(&error).map(ToString::to_string).as_deref(),
+            Some(r#""epol" is not assignable to configuration 'backend'"#)
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements validation of configuration keys as Rust identifiers.
+
+use crate::error::InvalidKeyReason;
+
+/// The prefix used by raw identifiers.
+const RAW_PREFIX: &str = "r#";
+
+/// Every strict or reserved keyword in the latest edition.
+///
+/// The derive macro keeps its own copy of this list, which its tests check against this one.
+const KEYWORDS: &[&str] = &[
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro",
+    "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super",
+    "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that may not be used as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["Self", "crate", "self", "super"];
+
+/// Validates that the given key is a valid Rust identifier, including raw identifiers.
+pub fn validate(key: &str) -> Result<(), InvalidKeyReason> {
+    if let Some(name) = key.strip_prefix(RAW_PREFIX) {
+        self::validate_characters(name)?;
+
+        return if NON_RAW_KEYWORDS.contains(&name) { Err(InvalidKeyReason::RawKeyword) } else { Ok(()) };
+    }
+
+    self::validate_characters(key)?;
+
+    if KEYWORDS.contains(&key) { Err(InvalidKeyReason::Keyword) } else { Ok(()) }
+}
+
+/// Validates that the given name is made up of valid identifier characters.
+fn validate_characters(name: &str) -> Result<(), InvalidKeyReason> {
+    let mut characters = name.chars();
+
+    match characters.next() {
+        None => return Err(InvalidKeyReason::Empty),
+        Some('_') if name.len() == 1 => return Err(InvalidKeyReason::Underscore),
+        Some(c) if c != '_' && !unicode_ident::is_xid_start(c) => return Err(InvalidKeyReason::InvalidStart(c)),
+        Some(_) => {}
+    }
+
+    characters
+        .find(|c| !unicode_ident::is_xid_continue(*c))
+        .map_or(Ok(()), |c| Err(InvalidKeyReason::InvalidCharacter(c)))
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements configurations that are assigned integers within a range.
+
+use std::num::IntErrorKind;
+use std::ops::RangeInclusive;
+
+use crate::{CheckedCfg, Error};
+
+/// A configuration that is assigned an integer within a range, created by [`Cfg::assigned_integer_in`].
+///
+/// [`Cfg::assigned_integer_in`]: crate::Cfg::assigned_integer_in
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegerCfg<'s> {
+    /// The configuration key.
+    key: &'s str,
+    /// The integers that may be assigned.
+    range: RangeInclusive<i64>,
+}
+
+impl<'s> IntegerCfg<'s> {
+    /// The largest number of integers that a configuration's range may contain.
+    pub const MAX_VALUES: usize = 1024;
+
+    /// Creates a new [`IntegerCfg`] without declaring it.
+    pub(crate) const fn new(key: &'s str, range: RangeInclusive<i64>) -> Self {
+        Self { key, range }
+    }
+
+    /// Returns the integers that may be assigned to this configuration.
+    #[must_use]
+    pub const fn range(&self) -> &RangeInclusive<i64> {
+        &self.range
+    }
+
+    /// Parses the given value as an integer within this configuration's range.
+    ///
+    /// Surrounding whitespace is ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the value is not an integer, or is outside of the range.
+    pub fn parse(&self, value: &str) -> Result<i64, Error> {
+        let out_of_range = || Error::IntegerOutOfRange {
+            key: self.key.into(),
+            value: value.trim().into(),
+            start: *self.range.start(),
+            end: *self.range.end(),
+        };
+
+        match value.trim().parse::<i64>() {
+            Ok(integer) if self.range.contains(&integer) => Ok(integer),
+            Ok(_) => Err(out_of_range()),
+            Err(error) if matches!(error.kind(), IntErrorKind::PosOverflow | IntErrorKind::NegOverflow) => {
+                Err(out_of_range())
+            }
+            Err(_) => Err(Error::InvalidInteger { key: self.key.into(), value: value.into() }),
+        }
+    }
+
+    /// Sets the configuration for the current build to the given integer.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the integer is outside of the range, or the directive could not be emitted.
+    pub fn set_integer(&self, value: i64) {
+        self.try_set_integer(value).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Sets the configuration for the current build to the given integer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the integer is outside of the range, or the directive could not be
+    /// emitted.
+    pub fn try_set_integer(&self, value: i64) -> Result<(), Error> {
+        if !self.range.contains(&value) {
+            return Err(Error::IntegerOutOfRange {
+                key: self.key.into(),
+                value: value.to_string().into_boxed_str(),
+                start: *self.range.start(),
+                end: *self.range.end(),
+            });
+        }
+
+        self.try_set(Some(&value.to_string()))
+    }
+}
+
+impl<'s> CheckedCfg<'s> for IntegerCfg<'s> {
+    fn key(&self) -> &'s str {
+        self.key
+    }
+
+    fn is_assignable(&self, value: Option<&str>) -> bool {
+        // Only the canonical form of each integer is declared, so values such as `+1` or `01` are rejected.
+        value
+            .and_then(|v| v.parse::<i64>().ok().filter(|i| i.to_string() == v))
+            .is_some_and(|i| self.range.contains(&i))
+    }
+
+    fn try_set_from_env_value(&self, value: Option<&str>) -> Result<(), Error> {
+        value.map_or(Ok(()), |value| self.try_set_integer(self.parse(value)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use super::IntegerCfg;
+    use crate::testing::Harness;
+    use crate::{Cfg, Error};
+
+    /// Declares an integer configuration with the given range, returning the result.
+    fn declare(range: RangeInclusive<i64>) -> Result<(), Error> {
+        Harness::new().run(|| Cfg::try_new("level")?.try_assigned_integer_in(range).map(drop)).0
+    }
+
+    #[test]
+    fn ranges_are_capped() {
+        let last = i64::try_from(IntegerCfg::MAX_VALUES).unwrap_or_else(|error| panic!("{error}")) - 1;
+
+        assert!(declare(0..=last).is_ok());
+        assert!(matches!(declare(0..=last + 1), Err(Error::RangeTooLarge { .. })));
+        assert!(matches!(declare(i64::MIN..=i64::MAX), Err(Error::RangeTooLarge { .. })));
+        assert!(matches!(declare(RangeInclusive::new(1, 0)), Err(Error::EmptyValues { .. })));
+    }
+
+    #[test]
+    fn values_are_parsed_within_the_range() {
+        let cfg = IntegerCfg::new("level", -2..=2);
+
+        assert_eq!(cfg.parse(" -2 ").ok(), Some(-2));
+        assert!(matches!(cfg.parse("3"), Err(Error::IntegerOutOfRange { .. })));
+        assert!(matches!(cfg.parse("99999999999999999999"), Err(Error::IntegerOutOfRange { .. })));
+        assert!(matches!(cfg.parse("two"), Err(Error::InvalidInteger { .. })));
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements ladders of flags that are set for every version threshold that has been met.
+
+use crate::version::Version;
+use crate::{Cfg, Directive, Error};
+
+/// A set of flags, one per version threshold, created by [`Cfg::version_ladder`].
+///
+/// Setting the ladder to a version sets the flag of every threshold that the version meets, so code that requires a
+/// version can be gated on a single flag, such as `#[cfg(proto_ge_2)]`.
+///
+/// ```no_run
+/// use fig::Cfg;
+///
+/// // Declares `proto_ge_1`, `proto_ge_2`, and `proto_ge_3_1`, then sets `proto_ge_1` and `proto_ge_2`.
+/// Cfg::version_ladder("proto", &["1", "2", "3.1"]).set("2.4");
+/// ```
+#[must_use = "this value does nothing unless used"]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionLadder {
+    /// Each threshold and the key of its flag, in the order that they were given.
+    rungs: Box<[(Version, Box<str>)]>,
+}
+
+impl VersionLadder {
+    /// Declares a flag for each of the given thresholds, each named `{prefix}_ge_{threshold}`.
+    pub(crate) fn try_declare(prefix: &str, thresholds: &[&str]) -> Result<Self, Error> {
+        if thresholds.is_empty() {
+            return Err(Error::EmptyValues { key: prefix.into() });
+        }
+
+        let rungs = thresholds
+            .iter()
+            .map(|&threshold| {
+                let version = Version::parse(threshold)?;
+                let key = format!("{prefix}_ge_{}", threshold.replace('.', "_"));
+
+                Cfg::try_new(&key)?.try_assigned_none()?;
+
+                Ok((version, key.into_boxed_str()))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self { rungs })
+    }
+
+    /// Returns an iterator over every threshold and the key of its flag.
+    pub fn rungs(&self) -> impl Iterator<Item = (Version, &str)> {
+        self.rungs.iter().map(|(version, key)| (*version, &**key))
+    }
+
+    /// Sets the flag of every threshold that the given version meets.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the version could not be parsed, or a directive could not be emitted.
+    pub fn set(&self, version: &str) {
+        self.try_set(version).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Sets the flag of every threshold that the given version meets.
+    ///
+    /// Surrounding whitespace is ignored, and a missing minor or patch version is treated as zero. Build metadata, such
+    /// as `+build.5`, is ignored, and a pre-release, such as `3.1.0-rc.1`, precedes its release, so it only meets the
+    /// thresholds below `3.1.0`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the version could not be parsed, or a directive could not be emitted.
+    pub fn try_set(&self, version: &str) -> Result<(), Error> {
+        let version = version.trim();
+        let error = || Error::InvalidVersion { version: version.into() };
+        let (release, build) = version.split_once('+').map_or((version, None), |(r, b)| (r, Some(b)));
+        let (release, pre) = release.split_once('-').map_or((release, None), |(r, p)| (r, Some(p)));
+
+        if build == Some("") || pre == Some("") {
+            return Err(error());
+        }
+
+        let release = Version::parse(release).map_err(|_| error())?;
+
+        if pre.is_some() {
+            self.try_set_where(|threshold| release > threshold)
+        } else {
+            self.try_set_where(|threshold| release >= threshold)
+        }
+    }
+
+    /// Sets the flag of every threshold that the given version meets.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a directive could not be emitted.
+    pub fn set_version(&self, version: Version) {
+        self.try_set_version(version).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Sets the flag of every threshold that the given version meets.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a directive could not be emitted.
+    pub fn try_set_version(&self, version: Version) -> Result<(), Error> {
+        self.try_set_where(|threshold| version >= threshold)
+    }
+
+    /// Sets the flag of every threshold that matches the given function.
+    fn try_set_where(&self, f: impl Fn(Version) -> bool) -> Result<(), Error> {
+        for (threshold, key) in self.rungs() {
+            if f(threshold) {
+                crate::sink::emit(Directive::Cfg { key: key.into(), value: None })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the flag of every threshold that the version within the given environment variable meets.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the version could not be parsed, or the given key contains an invalid character.
+    pub fn set_from_env(&self, variable_key: &str) {
+        self.try_set_from_env(variable_key).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Sets the flag of every threshold that the version within the given environment variable meets.
+    ///
+    /// If the variable is not present, no flags are set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the version could not be parsed, the variable does not contain valid
+    /// unicode, or the given key contains an invalid character.
+    pub fn try_set_from_env(&self, variable_key: &str) -> Result<(), Error> {
+        crate::env::read(variable_key)?
+            .map_or(Ok(()), |version| self.try_set(&version).map_err(|error| error.in_variable(variable_key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::Harness;
+    use crate::{Cfg, Directive, Error};
+
+    /// Sets a ladder of `proto` flags to the given version, returning the result and the keys of the set flags.
+    fn set(version: &str) -> (Result<(), Error>, Vec<Box<str>>) {
+        let (result, directives) =
+            Harness::new().run(|| Cfg::try_version_ladder("proto", &["1", "2", "3.1"])?.try_set(version));
+        let keys = directives
+            .into_iter()
+            .filter_map(|directive| match directive {
+                Directive::Cfg { key, value: None } => Some(key),
+                _ => None,
+            })
+            .collect();
+
+        (result, keys)
+    }
+
+    #[test]
+    fn releases_meet_every_threshold_up_to_them() {
+        assert_eq!(set(" 2.4 ").1, ["proto_ge_1".into(), "proto_ge_2".into()]);
+        assert_eq!(set("3.1.0").1, ["proto_ge_1".into(), "proto_ge_2".into(), "proto_ge_3_1".into()]);
+        assert_eq!(set("3.1.0+build.5").1, ["proto_ge_1".into(), "proto_ge_2".into(), "proto_ge_3_1".into()]);
+        assert!(set("0.9").1.is_empty());
+    }
+
+    #[test]
+    fn pre_releases_precede_their_release() {
+        let (result, keys) = set("3.1.0-rc.1");
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(keys, ["proto_ge_1".into(), "proto_ge_2".into()]);
+        assert_eq!(set("2.0.0-alpha+build").1, ["proto_ge_1".into()]);
+        assert_eq!(set("2.0.1-alpha").1, ["proto_ge_1".into(), "proto_ge_2".into()]);
+    }
+
+    #[test]
+    fn invalid_versions_are_rejected() {
+        for version in ["", "3.1.0-", "3.1.0+", "-rc.1", "3.x", "1.2.3.4"] {
+            let (result, keys) = set(version);
+
+            assert!(
+                matches!(&result, Err(Error::InvalidVersion { version: v }) if **v == *version.trim()),
+                "{version:?}: {result:?}"
+            );
+            assert!(keys.is_empty(), "{version:?}");
+        }
+    }
+}
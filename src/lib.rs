@@ -16,28 +16,48 @@
 
 //! Provides a simple API for declaring custom `cfg` predicates at compile-time.
 
-use std::env::VarError;
-
-/// Converts the given list of strings into a valid value string.
-fn list_to_value_str(values: &[&str]) -> Box<str> {
-    const SEPARATOR: &str = ", ";
-
-    let final_index = values.len().saturating_sub(1);
-    let initial_capcity = values.iter().map(|s| s.len()).sum::<usize>() + (SEPARATOR.len() * final_index);
-    let mut value_string = String::with_capacity(initial_capcity);
-
-    for (index, value) in values.iter().enumerate() {
-        value_string.push('"');
-        value_string += value;
-        value_string.push('"');
-
-        if index != final_index {
-            value_string += SEPARATOR;
-        }
-    }
-
-    value_string.into_boxed_str()
-}
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+
+#[cfg(feature = "toml")]
+pub use self::config::{from_file, from_manifest, try_from_file, try_from_manifest};
+pub use self::directive::Directive;
+use self::directive::Values;
+pub use self::error::Error;
+pub use self::integer::IntegerCfg;
+pub use self::ladder::VersionLadder;
+use self::normalize::{Normalize, Normalized};
+use self::pattern::Validator;
+pub use self::predicate::Predicate;
+pub use self::registry::Registry;
+pub use self::schema::{CfgEnum, CfgField, CfgSchema};
+pub use self::select::Select;
+pub use self::target::Target;
+#[cfg(feature = "derive")]
+pub use fig_derive::Cfg;
+
+#[cfg(feature = "toml")]
+pub mod config;
+pub mod directive;
+mod env;
+pub mod error;
+mod ident;
+pub mod integer;
+pub mod ladder;
+mod macros;
+pub mod normalize;
+pub mod pattern;
+pub mod predicate;
+mod probe;
+mod process;
+pub mod registry;
+pub mod rustc;
+pub mod schema;
+pub mod select;
+pub mod sink;
+pub mod target;
+pub mod testing;
+pub mod version;
 
 /// A custom configuration value.
 #[must_use = "this value does nothing unless used"]
@@ -48,14 +68,68 @@ pub struct Cfg<'s> {
 }
 
 impl<'i> Cfg<'i> {
-    /// Creates a new [`Cfg`] entry.
-    pub const fn new(key: &'i str) -> Self {
-        Self { key }
+    /// Creates a new [`Cfg`](struct@Cfg) entry.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the given key is not a valid identifier.
+    pub fn new(key: &'i str) -> Self {
+        Self::try_new(key).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Creates a new [`Cfg`](struct@Cfg) entry.
+    ///
+    /// Raw identifiers such as `r#true` are accepted, and must be used for keys that are keywords.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the given key is not a valid identifier.
+    pub fn try_new(key: &'i str) -> Result<Self, Error> {
+        match self::ident::validate(key) {
+            Ok(()) => Ok(Self { key }),
+            Err(reason) => Err(Error::InvalidKey { key: key.into(), reason }),
+        }
     }
 
-    /// Declares that this configuration is not assigned any values and register it.
+    /// Declares a flag named `{prefix}_ge_{threshold}` for each of the given version thresholds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the list is empty, a threshold is not a valid version, or a declaration could not
+    /// be emitted.
+    pub fn version_ladder(prefix: &str, thresholds: &[&str]) -> VersionLadder {
+        Self::try_version_ladder(prefix, thresholds).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares a flag named `{prefix}_ge_{threshold}` for each of the given version thresholds.
+    ///
+    /// Dots within each threshold are replaced with underscores, so `3.1` declares `{prefix}_ge_3_1`. None of the flags
+    /// are set until the returned [`VersionLadder`] is given a version.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the list is empty, a threshold is not a valid version, or a declaration
+    /// could not be emitted.
+    pub fn try_version_ladder(prefix: &str, thresholds: &[&str]) -> Result<VersionLadder, Error> {
+        VersionLadder::try_declare(prefix, thresholds)
+    }
+
+    /// Declares that this configuration is not assigned any values and registers it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the declaration could not be emitted.
     #[must_use = "this value does nothing unless used"]
     pub fn assigned_none(self) -> impl CheckedCfg<'i> {
+        self.try_assigned_none().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is not assigned any values and registers it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the declaration could not be emitted.
+    pub fn try_assigned_none(self) -> Result<impl CheckedCfg<'i>, Error> {
         struct Impl<'i>(&'i str);
 
         impl<'i> CheckedCfg<'i> for Impl<'i> {
@@ -68,14 +142,27 @@ impl<'i> Cfg<'i> {
             }
         }
 
-        println!("cargo::rustc-check-cfg=cfg({}, values(none()))", self.key);
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::None })?;
 
-        Impl(self.key)
+        Ok(Impl(self.key))
     }
 
-    /// Declares that this configuration is not assigned any values and register it.
+    /// Declares that this configuration is assigned any value and registers it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the declaration could not be emitted.
     #[must_use = "this value does nothing unless used"]
     pub fn assigned_any(self) -> impl CheckedCfg<'i> {
+        self.try_assigned_any().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is assigned any value and registers it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the declaration could not be emitted.
+    pub fn try_assigned_any(self) -> Result<impl CheckedCfg<'i>, Error> {
         struct Impl<'i>(&'i str);
 
         impl<'i> CheckedCfg<'i> for Impl<'i> {
@@ -88,18 +175,75 @@ impl<'i> Cfg<'i> {
             }
         }
 
-        println!("cargo::rustc-check-cfg=cfg({}, values(any()))", self.key);
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::Any })?;
 
-        Impl(self.key)
+        Ok(Impl(self.key))
     }
 
-    /// Declares that this configuration is not assigned any values and register it.
+    /// Declares that this configuration is assigned any value accepted by the given validator and registers it.
     ///
     /// # Panics
     ///
-    /// This function will panic if the provided list is empty.
+    /// This function will panic if the declaration could not be emitted.
+    #[must_use = "this value does nothing unless used"]
+    pub fn assigned_matching<V: Validator>(self, validator: V) -> impl CheckedCfg<'i> {
+        self.try_assigned_matching(validator).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is assigned any value accepted by the given validator and registers it.
+    ///
+    /// The configuration is registered as accepting any value, as the accepted values cannot be listed. The validator
+    /// may be a closure that returns the reason a value was rejected, or a [`Pattern`](crate::pattern::Pattern).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the declaration could not be emitted.
+    pub fn try_assigned_matching<V: Validator>(self, validator: V) -> Result<impl CheckedCfg<'i>, Error> {
+        struct Impl<'i, V>(&'i str, V);
+
+        impl<'i, V: Validator> CheckedCfg<'i> for Impl<'i, V> {
+            fn key(&self) -> &'i str {
+                self.0
+            }
+
+            fn is_assignable(&self, value: Option<&str>) -> bool {
+                value.is_some_and(|v| self.1.validate(v).is_ok())
+            }
+
+            fn check_assignable(&self, value: Option<&str>) -> Result<(), Error> {
+                let Some(value) = value else {
+                    return Err(Error::Unassignable { key: self.0.into(), value: None, allowed: Box::default() });
+                };
+
+                self.1.validate(value).map_err(|reason| Error::Rejected {
+                    key: self.0.into(),
+                    value: value.into(),
+                    reason: reason.into_boxed_str(),
+                })
+            }
+        }
+
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::Any })?;
+
+        Ok(Impl(self.key, validator))
+    }
+
+    /// Declares that this configuration is assigned exactly one of the given values and registers it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the provided list is empty, or the declaration could not be emitted.
     #[must_use = "this value does nothing unless used"]
     pub fn assigned_one_of(self, values: &'i [&'i str]) -> impl CheckedCfg<'i> {
+        self.try_assigned_one_of(values).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is assigned exactly one of the given values and registers it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the provided list is empty, or the declaration could not be emitted.
+    pub fn try_assigned_one_of(self, values: &'i [&'i str]) -> Result<impl CheckedCfg<'i>, Error> {
         struct Impl<'i>(&'i str, &'i [&'i str]);
 
         impl<'i> CheckedCfg<'i> for Impl<'i> {
@@ -110,22 +254,39 @@ impl<'i> Cfg<'i> {
             fn is_assignable(&self, value: Option<&str>) -> bool {
                 value.is_some_and(|v| self.1.contains(&v))
             }
+
+            fn allowed_values(&self) -> Option<&[&'i str]> {
+                Some(self.1)
+            }
         }
 
-        assert!(!values.is_empty(), "at least one value should be provided");
+        if values.is_empty() {
+            return Err(Error::EmptyValues { key: self.key.into() });
+        }
 
-        println!("cargo::rustc-check-cfg=cfg({}, values({}))", self.key, self::list_to_value_str(values));
+        let list = values.iter().map(|&v| v.into()).collect();
 
-        Impl(self.key, values)
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::OneOf(list) })?;
+
+        Ok(Impl(self.key, values))
     }
 
-    /// Declares that this configuration is not assigned any values and register it.
+    /// Declares that this configuration is assigned either no value or one of the given values and registers it.
     ///
     /// # Panics
     ///
-    /// This function will panic if the provided list is empty.
+    /// This function will panic if the provided list is empty, or the declaration could not be emitted.
     #[must_use = "this value does nothing unless used"]
     pub fn assigned_none_or_one_of(self, values: &'i [&'i str]) -> impl CheckedCfg<'i> {
+        self.try_assigned_none_or_one_of(values).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is assigned either no value or one of the given values and registers it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the provided list is empty, or the declaration could not be emitted.
+    pub fn try_assigned_none_or_one_of(self, values: &'i [&'i str]) -> Result<impl CheckedCfg<'i>, Error> {
         struct Impl<'i>(&'i str, &'i [&'i str]);
 
         impl<'i> CheckedCfg<'i> for Impl<'i> {
@@ -136,13 +297,302 @@ impl<'i> Cfg<'i> {
             fn is_assignable(&self, value: Option<&str>) -> bool {
                 value.is_none_or(|v| self.1.contains(&v))
             }
+
+            fn allowed_values(&self) -> Option<&[&'i str]> {
+                Some(self.1)
+            }
+        }
+
+        if values.is_empty() {
+            return Err(Error::EmptyValues { key: self.key.into() });
         }
 
-        assert!(!values.is_empty(), "at least one value should be provided");
+        let list = values.iter().map(|&v| v.into()).collect();
 
-        println!("cargo::rustc-check-cfg=cfg({}, values(none(), {}))", self.key, self::list_to_value_str(values));
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::NoneOrOneOf(list) })?;
+
+        Ok(Impl(self.key, values))
+    }
 
-        Impl(self.key, values)
+    /// Declares that this configuration is assigned an integer within the given range and registers it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the range is empty, contains more than [`IntegerCfg::MAX_VALUES`] integers, or the
+    /// declaration could not be emitted.
+    #[must_use = "this value does nothing unless used"]
+    pub fn assigned_integer_in(self, range: RangeInclusive<i64>) -> IntegerCfg<'i> {
+        self.try_assigned_integer_in(range).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is assigned an integer within the given range and registers it.
+    ///
+    /// Every integer within the range is listed in the declaration, so the range may contain at most
+    /// [`IntegerCfg::MAX_VALUES`] integers.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the range is empty, contains more than [`IntegerCfg::MAX_VALUES`]
+    /// integers, or the declaration could not be emitted.
+    pub fn try_assigned_integer_in(self, range: RangeInclusive<i64>) -> Result<IntegerCfg<'i>, Error> {
+        if range.is_empty() {
+            return Err(Error::EmptyValues { key: self.key.into() });
+        }
+
+        let (start, end) = (*range.start(), *range.end());
+
+        if i128::from(end) - i128::from(start) >= IntegerCfg::MAX_VALUES as i128 {
+            return Err(Error::RangeTooLarge { key: self.key.into(), start, end, limit: IntegerCfg::MAX_VALUES });
+        }
+
+        let list = range.clone().map(|integer| integer.to_string().into_boxed_str()).collect();
+
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::OneOf(list) })?;
+
+        Ok(IntegerCfg::new(self.key, range))
+    }
+
+    /// Declares that this configuration is a flag, which is either set without a value or not set, and registers it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the declaration could not be emitted.
+    #[must_use = "this value does nothing unless used"]
+    pub fn flag(self) -> impl CheckedCfg<'i> {
+        self.try_flag().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is a flag, which is either set without a value or not set, and registers it.
+    ///
+    /// When set from an environment variable, `1`, `true`, `yes`, and `on` set the flag, while `0`, `false`, `no`, and
+    /// `off` leave it unset. Values are compared without regard to case, and any other value is rejected.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the declaration could not be emitted.
+    pub fn try_flag(self) -> Result<impl CheckedCfg<'i>, Error> {
+        struct Impl<'i>(&'i str);
+
+        impl<'i> CheckedCfg<'i> for Impl<'i> {
+            fn key(&self) -> &'i str {
+                self.0
+            }
+
+            fn is_assignable(&self, value: Option<&str>) -> bool {
+                value.is_none()
+            }
+
+            fn try_set_from_env_value(&self, value: Option<&str>) -> Result<(), Error> {
+                match value.map(|value| (value, self::env::parse_flag(value))) {
+                    None | Some((_, Some(false))) => Ok(()),
+                    Some((_, Some(true))) => self.try_set(None),
+                    Some((value, None)) => Err(Error::Unassignable {
+                        key: self.0.into(),
+                        value: Some(value.into()),
+                        allowed: self::env::FLAG_VALUES.map(Into::into).into(),
+                    }),
+                }
+            }
+        }
+
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::None })?;
+
+        Ok(Impl(self.key))
+    }
+
+    /// Declares that this configuration is assigned any number of the given values at once and registers it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the provided list is empty, or the declaration could not be emitted.
+    #[must_use = "this value does nothing unless used"]
+    pub fn assigned_any_of(self, values: &'i [&'i str]) -> impl CheckedCfg<'i> {
+        self.try_assigned_any_of(values).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares that this configuration is assigned any number of the given values at once and registers it.
+    ///
+    /// Like `target_feature`, each value is set separately, using [`CheckedCfg::set_many`] or
+    /// [`CheckedCfg::set_from_env_list`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the provided list is empty, or the declaration could not be emitted.
+    pub fn try_assigned_any_of(self, values: &'i [&'i str]) -> Result<impl CheckedCfg<'i>, Error> {
+        struct Impl<'i>(&'i str, &'i [&'i str]);
+
+        impl<'i> CheckedCfg<'i> for Impl<'i> {
+            fn key(&self) -> &'i str {
+                self.0
+            }
+
+            fn is_assignable(&self, value: Option<&str>) -> bool {
+                value.is_some_and(|v| self.1.contains(&v))
+            }
+
+            fn allowed_values(&self) -> Option<&[&'i str]> {
+                Some(self.1)
+            }
+        }
+
+        if values.is_empty() {
+            return Err(Error::EmptyValues { key: self.key.into() });
+        }
+
+        let list = values.iter().map(|&v| v.into()).collect();
+
+        self::sink::emit(Directive::CheckCfg { key: self.key.into(), values: Values::AnyOf(list) })?;
+
+        Ok(Impl(self.key, values))
+    }
+
+    /// Declares this configuration as an alias for the given predicate, setting it if the predicate holds.
+    ///
+    /// Returns `true` if the configuration was set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the predicate is invalid, the target could not be detected, or a directive could
+    /// not be emitted.
+    #[expect(clippy::must_use_candidate, reason = "the configuration is set as a side effect")]
+    pub fn alias(self, predicate: &str) -> bool {
+        self.try_alias(predicate).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares this configuration as an alias for the given predicate, setting it if the predicate holds.
+    ///
+    /// The predicate is evaluated against the current target, the enabled Cargo features, and every configuration
+    /// that has already been set, so aliases may refer to each other. Returns `true` if the configuration was set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the predicate is invalid, the target could not be detected, or a
+    /// directive could not be emitted.
+    pub fn try_alias(self, predicate: &str) -> Result<bool, Error> {
+        self.try_alias_predicate(&Predicate::parse(predicate)?)
+    }
+
+    /// Declares this configuration as an alias for the given predicate, setting it if the predicate holds.
+    ///
+    /// Returns `true` if the configuration was set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the target could not be detected, or a directive could not be emitted.
+    #[expect(clippy::must_use_candidate, reason = "the configuration is set as a side effect")]
+    pub fn alias_predicate(self, predicate: &Predicate) -> bool {
+        self.try_alias_predicate(predicate).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares this configuration as an alias for the given predicate, setting it if the predicate holds.
+    ///
+    /// Returns `true` if the configuration was set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the target could not be detected, or a directive could not be emitted.
+    pub fn try_alias_predicate(self, predicate: &Predicate) -> Result<bool, Error> {
+        let cfg = self.try_assigned_none()?;
+        let holds = predicate.try_evaluate_current()?;
+
+        if holds {
+            cfg.try_set(None)?;
+        }
+
+        Ok(holds)
+    }
+
+    /// Declares this configuration and sets it if the given expression compiles, such as `1u32.ilog2()`.
+    ///
+    /// Returns `true` if the configuration was set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `OUT_DIR` is not set, the compiler could not be run, or a directive could not be
+    /// emitted.
+    #[expect(clippy::must_use_candidate, reason = "the configuration is set as a side effect")]
+    pub fn probe_expr(self, expr: &str) -> bool {
+        self.try_probe_expr(expr).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares this configuration and sets it if the given expression compiles, such as `1u32.ilog2()`.
+    ///
+    /// The expression is compiled as part of a small 2021 edition library with the compiler and target used by the current
+    /// build, writing its output into `OUT_DIR`. Returns `true` if the configuration was set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `OUT_DIR` is not set, the compiler could not be run, or a directive could
+    /// not be emitted.
+    pub fn try_probe_expr(self, expr: &str) -> Result<bool, Error> {
+        self.try_probe_with(|| self::probe::expr(expr))
+    }
+
+    /// Declares this configuration and sets it if the given type compiles, such as `core::num::NonZero<u8>`.
+    ///
+    /// Returns `true` if the configuration was set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `OUT_DIR` is not set, the compiler could not be run, or a directive could not be
+    /// emitted.
+    #[expect(clippy::must_use_candidate, reason = "the configuration is set as a side effect")]
+    pub fn probe_type(self, ty: &str) -> bool {
+        self.try_probe_type(ty).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares this configuration and sets it if the given type compiles, such as `core::num::NonZero<u8>`.
+    ///
+    /// The type is compiled as part of a small library with the compiler and target used by the current build, writing
+    /// its output into `OUT_DIR`. Returns `true` if the configuration was set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `OUT_DIR` is not set, the compiler could not be run, or a directive could
+    /// not be emitted.
+    pub fn try_probe_type(self, ty: &str) -> Result<bool, Error> {
+        self.try_probe_with(|| self::probe::ty(ty))
+    }
+
+    /// Declares this configuration and sets it if the given path can be imported, such as `std::sync::LazyLock`.
+    ///
+    /// Returns `true` if the configuration was set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `OUT_DIR` is not set, the compiler could not be run, or a directive could not be
+    /// emitted.
+    #[expect(clippy::must_use_candidate, reason = "the configuration is set as a side effect")]
+    pub fn probe_path(self, path: &str) -> bool {
+        self.try_probe_path(path).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares this configuration and sets it if the given path can be imported, such as `std::sync::LazyLock`.
+    ///
+    /// The path is compiled as part of a small library with the compiler and target used by the current build, writing
+    /// its output into `OUT_DIR`. Returns `true` if the configuration was set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `OUT_DIR` is not set, the compiler could not be run, or a directive could
+    /// not be emitted.
+    pub fn try_probe_path(self, path: &str) -> Result<bool, Error> {
+        self.try_probe_with(|| self::probe::path(path))
+    }
+
+    /// Declares this configuration and sets it if the given probe succeeds.
+    fn try_probe_with<F>(self, probe: F) -> Result<bool, Error>
+    where
+        F: FnOnce() -> Result<bool, Error>,
+    {
+        let cfg = self.try_assigned_none()?;
+        let compiles = probe()?;
+
+        if compiles {
+            cfg.try_set(None)?;
+        }
+
+        Ok(compiles)
     }
 }
 
@@ -154,18 +604,200 @@ pub trait CheckedCfg<'s> {
     /// Returns `true` if the value can be assigned to this configuration.
     fn is_assignable(&self, value: Option<&'_ str>) -> bool;
 
+    /// Returns every value that may be assigned to this configuration, or `None` if they cannot be listed.
+    ///
+    /// By default, this returns `None`.
+    fn allowed_values(&self) -> Option<&[&'s str]> {
+        None
+    }
+
+    /// Returns an error describing why the value cannot be assigned to this configuration, if it cannot.
+    ///
+    /// By default, this reports an [`Error::Unassignable`] whenever [`CheckedCfg::is_assignable`] returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the value cannot be assigned to this configuration.
+    fn check_assignable(&self, value: Option<&'_ str>) -> Result<(), Error> {
+        if self.is_assignable(value) {
+            Ok(())
+        } else {
+            Err(Error::Unassignable {
+                key: self.key().into(),
+                value: value.map(Into::into),
+                allowed: self.allowed_values().unwrap_or_default().iter().map(|&v| v.into()).collect(),
+            })
+        }
+    }
+
     /// Sets the configuration for the current build.
     ///
     /// # Panics
     ///
     /// This function will panic if the provided value is not assignable to the configuration.
     fn set(&self, value: Option<&'_ str>) {
-        assert!(self.is_assignable(value), "`{value:?}` is not assignable to configuration '{}'", self.key());
+        self.try_set(value).unwrap_or_else(|error| panic!("{error}"));
+    }
 
-        if let Some(value) = value {
-            println!(r#"cargo::rustc-cfg={}="{value}""#, self.key());
-        } else {
-            println!("cargo::rustc-cfg={}", self.key());
+    /// Sets the configuration for the current build.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the provided value is not assignable to the configuration, or the
+    /// directive could not be emitted.
+    fn try_set(&self, value: Option<&'_ str>) -> Result<(), Error> {
+        self.check_assignable(value)?;
+
+        self::sink::emit(Directive::Cfg { key: self.key().into(), value: value.map(Into::into) })
+    }
+
+    /// Normalizes a value read from an environment variable before it is assigned.
+    ///
+    /// By default, the value is returned unchanged.
+    fn normalize<'v>(&self, value: &'v str) -> Cow<'v, str> {
+        Cow::Borrowed(value)
+    }
+
+    /// Returns this configuration with the given normalization applied to values read from environment variables.
+    fn normalized(self, normalize: Normalize) -> Normalized<Self>
+    where
+        Self: Sized,
+    {
+        Normalized::new(self, normalize)
+    }
+
+    /// Sets the configuration for the current build from a normalized value read from an environment variable.
+    ///
+    /// By default, the value is assigned as-is. Configurations may override this to interpret the value first, such as
+    /// flags that treat `false` as leaving the configuration unset.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the provided value is not assignable to the configuration, or the
+    /// directive could not be emitted.
+    fn try_set_from_env_value(&self, value: Option<&'_ str>) -> Result<(), Error> {
+        self.try_set(value)
+    }
+
+    /// Sets the configuration for the current build to each of the given values.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any of the provided values are not assignable to the configuration.
+    fn set_many(&self, values: &[&str]) {
+        self.try_set_many(values).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Sets the configuration for the current build to each of the given values.
+    ///
+    /// Every value is checked before any are set, and one directive is emitted per value.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the provided values are not assignable to the configuration, or a
+    /// directive could not be emitted.
+    fn try_set_many(&self, values: &[&str]) -> Result<(), Error> {
+        values.iter().try_for_each(|&value| self.check_assignable(Some(value)))?;
+
+        values.iter().try_for_each(|&value| self.try_set(Some(value)))
+    }
+
+    /// Sets the configuration for the current build to each value within the given environment variable.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any of the values are not assignable to the configuration, or the given key
+    /// contains an invalid character.
+    fn set_from_env_list(&self, variable_key: &str, separator: char) {
+        self.try_set_from_env_list(variable_key, separator).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Sets the configuration for the current build to each value within the given environment variable.
+    ///
+    /// The variable is split on the given separator, surrounding whitespace is trimmed from each value, and empty
+    /// values are ignored. Each remaining value is then normalized. If the variable is not present, the configuration
+    /// is not set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the values are not assignable to the configuration, the variable
+    /// does not contain valid unicode, or the given key contains an invalid character.
+    fn try_set_from_env_list(&self, variable_key: &str, separator: char) -> Result<(), Error> {
+        let Some(list) = self::env::read(variable_key)? else { return Ok(()) };
+        let values = list.split(separator).map(str::trim).filter(|value| !value.is_empty());
+        let values = values.map(|value| self.normalize(value)).collect::<Vec<_>>();
+
+        self.try_set_many(&values.iter().map(AsRef::as_ref).collect::<Vec<_>>())
+            .map_err(|error| error.in_variable(variable_key))
+    }
+
+    /// Sets the configuration for the current build if the given predicate holds for the current target.
+    ///
+    /// Returns `true` if the configuration was set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the predicate is invalid, the target could not be detected, or the provided value
+    /// is not assignable to the configuration.
+    fn set_if(&self, predicate: &str, value: Option<&'_ str>) -> bool {
+        self.try_set_if(predicate, value).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Sets the configuration for the current build if the given predicate holds for the current target.
+    ///
+    /// The predicate is evaluated using [`Predicate::evaluate`]. Returns `true` if the configuration was set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the predicate is invalid, the target could not be detected, or the
+    /// provided value is not assignable to the configuration.
+    fn try_set_if(&self, predicate: &str, value: Option<&'_ str>) -> Result<bool, Error> {
+        let holds = Predicate::parse(predicate)?.try_evaluate_current()?;
+
+        if holds {
+            self.try_set(value)?;
+        }
+
+        Ok(holds)
+    }
+
+    /// Returns a [`Select`] that sets this configuration to the value of the first rule whose predicate holds.
+    fn select(&self) -> Select<'_, Self> {
+        Select::new(self)
+    }
+
+    /// Sets the configuration for the current build from the enabled Cargo feature within the given list.
+    ///
+    /// Each feature is paired with the value that it assigns, and the features are treated as mutually exclusive. If
+    /// none of the features are enabled, the configuration is not set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if more than one of the features is enabled, or the enabled feature's value is not
+    /// assignable to the configuration.
+    fn set_from_features(&self, features: &[(&str, Option<&str>)]) {
+        self.try_set_from_features(features).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Sets the configuration for the current build from the enabled Cargo feature within the given list.
+    ///
+    /// Each feature is paired with the value that it assigns, and the features are treated as mutually exclusive. If
+    /// none of the features are enabled, the configuration is not set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if more than one of the features is enabled, or the enabled feature's value
+    /// is not assignable to the configuration.
+    fn try_set_from_features(&self, features: &[(&str, Option<&str>)]) -> Result<(), Error> {
+        let enabled = features.iter().filter(|(feature, _)| self::env::has_feature(feature)).collect::<Vec<_>>();
+
+        match *enabled {
+            [] => Ok(()),
+            [&(_, value)] => self.try_set(value),
+            _ => Err(Error::ConflictingFeatures {
+                key: self.key().into(),
+                features: enabled.into_iter().map(|&(feature, _)| feature.into()).collect(),
+            }),
         }
     }
 
@@ -179,6 +811,16 @@ pub trait CheckedCfg<'s> {
         self.set_from_env_or_else(variable_key, || None);
     }
 
+    /// Sets the configuration for the current build from the given environment variable.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the provided value is not assignable to the configuration, the variable
+    /// does not contain valid unicode, or the given key contains an invalid character.
+    fn try_set_from_env(&self, variable_key: &str) -> Result<(), Error> {
+        self.try_set_from_env_or_else(variable_key, || None)
+    }
+
     /// Sets the configuration for the current build from the given environment variable.
     ///
     /// # Panics
@@ -189,12 +831,23 @@ pub trait CheckedCfg<'s> {
     where
         D: FnOnce() -> Option<String>,
     {
-        println!("cargo::rerun-if-env-changed={variable_key}");
+        self.try_set_from_env_or_else(variable_key, default).unwrap_or_else(|error| panic!("{error}"));
+    }
 
-        match std::env::var(variable_key) {
-            Ok(value) if !value.is_empty() => self.set(Some(&value)),
-            Ok(_) | Err(VarError::NotPresent) => self.set(default().as_deref()),
-            Err(error) => panic!("{error}"),
-        }
+    /// Sets the configuration for the current build from the given environment variable.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the provided value is not assignable to the configuration, the variable
+    /// does not contain valid unicode, or the given key contains an invalid character.
+    fn try_set_from_env_or_else<D>(&self, variable_key: &str, default: D) -> Result<(), Error>
+    where
+        D: FnOnce() -> Option<String>,
+    {
+        let Some(value) = self::env::read(variable_key)? else {
+            return self.try_set_from_env_value(default().as_deref().map(|value| self.normalize(value)).as_deref());
+        };
+
+        self.try_set_from_env_value(Some(&self.normalize(&value))).map_err(|error| error.in_variable(variable_key))
     }
 }
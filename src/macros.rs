@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Defines the macros exported by this crate.
+
+/// Declares a schema of configurations in a single block, optionally setting each of them.
+///
+/// Each entry is written as `key: kind`, where the kind is one of `none`, `flag`, `any`, `one_of(...)`, or
+/// `none_or_one_of(...)`. An entry may be followed by `, env "VARIABLE"` to set it from an environment variable, and by
+/// `= value` to set a default value, where the value is an `Option<&str>`.
+///
+/// An entry may instead be written as `key: alias("predicate")`, which sets it whenever the predicate holds.
+///
+/// # Panics
+///
+/// The expanded code will panic under the same conditions as the [`Cfg`](struct@crate::Cfg) and
+/// [`CheckedCfg`](crate::CheckedCfg) methods that it calls.
+///
+/// # Examples
+///
+/// ```no_run
+/// fig::declare! {
+///     // Declared, but never set.
+///     has_simd: none;
+///     // Set if `USE_SIMD` is truthy, such as `1` or `yes`.
+///     use_simd: flag, env "USE_SIMD";
+///     // Always set to `"foo"`.
+///     custom_cfg: one_of("foo", "bar") = Some("foo");
+///     // Set from `BACKEND`, falling back to `"poll"` if it is not present.
+///     backend: one_of("epoll", "kqueue", "poll"), env "BACKEND" = Some("poll");
+///     // Set from `LOG_LEVEL`, or left unset if it is not present.
+///     log_level: none_or_one_of("debug", "trace"), env "LOG_LEVEL";
+///     // Set when building for a browser.
+///     wasm_browser: alias(r#"all(target_arch = "wasm32", not(target_os = "wasi"))"#);
+/// }
+/// ```
+#[macro_export]
+macro_rules! declare {
+    ($(
+        $key:ident : $kind:ident $(( $($value:literal),+ $(,)? ))? $(, env $variable:literal)? $(= $default:expr)?;
+    )*) => {
+        $({
+            let cfg = $crate::declare!(@declare $key, $kind $(( $($value),+ ))?);
+
+            $crate::declare!(@set cfg, [$($variable)?], [$($default)?]);
+        })*
+    };
+    (@declare $key:ident, none) => {
+        $crate::Cfg::new(::core::stringify!($key)).assigned_none()
+    };
+    (@declare $key:ident, flag) => {
+        $crate::Cfg::new(::core::stringify!($key)).flag()
+    };
+    (@declare $key:ident, any) => {
+        $crate::Cfg::new(::core::stringify!($key)).assigned_any()
+    };
+    (@declare $key:ident, one_of($($value:literal),+)) => {
+        $crate::Cfg::new(::core::stringify!($key)).assigned_one_of(&[$($value),+])
+    };
+    (@declare $key:ident, none_or_one_of($($value:literal),+)) => {
+        $crate::Cfg::new(::core::stringify!($key)).assigned_none_or_one_of(&[$($value),+])
+    };
+    (@declare $key:ident, alias($predicate:literal)) => {
+        $crate::Cfg::new(::core::stringify!($key)).alias($predicate)
+    };
+    (@set $cfg:ident, [], []) => {
+        let _ = $cfg;
+    };
+    (@set $cfg:ident, [], [$default:expr]) => {
+        $crate::CheckedCfg::set(&$cfg, $default);
+    };
+    (@set $cfg:ident, [$variable:literal], []) => {
+        $crate::CheckedCfg::set_from_env(&$cfg, $variable);
+    };
+    (@set $cfg:ident, [$variable:literal], [$default:expr]) => {
+        $crate::CheckedCfg::set_from_env_or_else(&$cfg, $variable, || {
+            ::core::option::Option::<&str>::map($default, ::std::string::ToString::to_string)
+        });
+    };
+}
@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements normalization of values read from environment variables.
+
+use std::borrow::Cow;
+
+use crate::{CheckedCfg, Error};
+
+/// The normalization applied to values read from environment variables, in the order that the options are listed.
+///
+/// ```no_run
+/// use fig::normalize::Normalize;
+/// use fig::{Cfg, CheckedCfg};
+///
+/// let normalize = Normalize::new().trim().lowercase().alias("gnu", "glibc");
+///
+/// // `LIBC=" GNU"` sets `libc = "glibc"`.
+/// Cfg::new("libc").assigned_one_of(&["glibc", "musl"]).normalized(normalize).set_from_env("LIBC");
+/// ```
+#[must_use = "this value does nothing unless used"]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Normalize {
+    /// Whether surrounding whitespace is removed.
+    trim: bool,
+    /// Whether ASCII characters are converted to lowercase.
+    lowercase: bool,
+    /// The aliases that are replaced with their canonical values.
+    aliases: Vec<(Box<str>, Box<str>)>,
+}
+
+impl Normalize {
+    /// Creates a new [`Normalize`] that leaves values unchanged.
+    pub const fn new() -> Self {
+        Self { trim: false, lowercase: false, aliases: Vec::new() }
+    }
+
+    /// Removes surrounding whitespace from values.
+    pub const fn trim(mut self) -> Self {
+        self.trim = true;
+
+        self
+    }
+
+    /// Converts ASCII characters within values to lowercase.
+    pub const fn lowercase(mut self) -> Self {
+        self.lowercase = true;
+
+        self
+    }
+
+    /// Replaces the given alias with the given canonical value.
+    ///
+    /// Aliases are compared after values have been trimmed and converted to lowercase.
+    pub fn alias(mut self, alias: impl Into<Box<str>>, value: impl Into<Box<str>>) -> Self {
+        self.aliases.push((alias.into(), value.into()));
+
+        self
+    }
+
+    /// Applies this normalization to the given value.
+    #[must_use]
+    pub fn apply<'v>(&self, value: &'v str) -> Cow<'v, str> {
+        let mut value = Cow::Borrowed(if self.trim { value.trim() } else { value });
+
+        if self.lowercase && value.chars().any(|c| c.is_ascii_uppercase()) {
+            value = Cow::Owned(value.to_ascii_lowercase());
+        }
+
+        match self.aliases.iter().find(|(alias, _)| **alias == *value) {
+            Some((_, canonical)) => Cow::Owned(canonical.to_string()),
+            None => value,
+        }
+    }
+}
+
+/// A configuration whose environment values are normalized before being assigned, created by
+/// [`CheckedCfg::normalized`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Normalized<C> {
+    /// The wrapped configuration.
+    cfg: C,
+    /// The normalization applied to values.
+    normalize: Normalize,
+}
+
+impl<C> Normalized<C> {
+    /// Creates a new [`Normalized`] configuration.
+    pub(crate) const fn new(cfg: C, normalize: Normalize) -> Self {
+        Self { cfg, normalize }
+    }
+}
+
+impl<'s, C: CheckedCfg<'s>> CheckedCfg<'s> for Normalized<C> {
+    fn key(&self) -> &'s str {
+        self.cfg.key()
+    }
+
+    fn is_assignable(&self, value: Option<&str>) -> bool {
+        self.cfg.is_assignable(value)
+    }
+
+    fn allowed_values(&self) -> Option<&[&'s str]> {
+        self.cfg.allowed_values()
+    }
+
+    fn check_assignable(&self, value: Option<&str>) -> Result<(), Error> {
+        self.cfg.check_assignable(value)
+    }
+
+    fn normalize<'v>(&self, value: &'v str) -> Cow<'v, str> {
+        match self.normalize.apply(value) {
+            Cow::Borrowed(value) => self.cfg.normalize(value),
+            Cow::Owned(value) => Cow::Owned(self.cfg.normalize(&value).into_owned()),
+        }
+    }
+
+    fn try_set_from_env_value(&self, value: Option<&str>) -> Result<(), Error> {
+        self.cfg.try_set_from_env_value(value)
+    }
+}
@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements validation of free-form configuration values.
+
+use std::fmt::Display;
+use std::str::{Chars, FromStr};
+
+use crate::Error;
+
+/// Checks whether a value may be assigned to a configuration, created by [`Cfg::assigned_matching`].
+///
+/// This is implemented for closures that return the reason that a value was rejected, and for [`Pattern`].
+///
+/// [`Cfg::assigned_matching`]: crate::Cfg::assigned_matching
+pub trait Validator {
+    /// Returns the reason that the given value is rejected, if it is.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the value may not be assigned.
+    fn validate(&self, value: &str) -> Result<(), String>;
+}
+
+impl<F> Validator for F
+where
+    F: Fn(&str) -> Result<(), String>,
+{
+    fn validate(&self, value: &str) -> Result<(), String> {
+        self(value)
+    }
+}
+
+/// A glob pattern that values must match.
+///
+/// Patterns support `*` for any sequence of characters, `?` for any single character, and character classes such as
+/// `[a-z_]` or `[!0-9]`. Any special character may be matched literally by preceding it with `\`, including `]` and
+/// `-` within a character class. A `]` at the start of a class is also matched literally.
+///
+/// ```
+/// use fig::pattern::Pattern;
+///
+/// let pattern = Pattern::new("v[0-9]*")?;
+///
+/// assert!(pattern.matches("v1"));
+/// assert!(pattern.matches("v2-beta"));
+/// assert!(!pattern.matches("version"));
+/// # Ok::<(), fig::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    /// The pattern source.
+    source: Box<str>,
+    /// The compiled tokens.
+    tokens: Box<[Token]>,
+}
+
+/// A single token within a [`Pattern`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Token {
+    /// Matches the given character.
+    Char(char),
+    /// Matches any single character.
+    AnyChar,
+    /// Matches any sequence of characters, including an empty one.
+    AnySequence,
+    /// Matches any character within one of the given inclusive ranges, or outside of all of them if negated.
+    Class {
+        /// Whether the class is negated.
+        negated: bool,
+        /// The inclusive ranges of characters.
+        ranges: Box<[(char, char)]>,
+    },
+}
+
+impl Token {
+    /// Returns `true` if this token matches the given character.
+    fn matches(&self, character: char) -> bool {
+        match self {
+            Self::Char(expected) => *expected == character,
+            Self::AnyChar => true,
+            Self::AnySequence => false,
+            Self::Class { negated, ranges } => {
+                ranges.iter().any(|&(start, end)| (start..=end).contains(&character)) != *negated
+            }
+        }
+    }
+}
+
+impl Pattern {
+    /// Compiles the given glob pattern.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the pattern contains an unclosed character class or a trailing `\`.
+    pub fn new(source: &str) -> Result<Self, Error> {
+        let error = |message: &str| Error::InvalidPattern { pattern: source.into(), message: message.into() };
+        let mut tokens = Vec::new();
+        let mut characters = source.chars();
+
+        while let Some(character) = characters.next() {
+            let token = match character {
+                '*' => Token::AnySequence,
+                '?' => Token::AnyChar,
+                '\\' => Token::Char(characters.next().ok_or_else(|| error("trailing `\\`"))?),
+                '[' => self::class(&mut characters).ok_or_else(|| error("unclosed character class"))?,
+                c => Token::Char(c),
+            };
+
+            tokens.push(token);
+        }
+
+        Ok(Self { source: source.into(), tokens: tokens.into_boxed_slice() })
+    }
+
+    /// Returns the pattern source.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns `true` if the entire value matches this pattern.
+    #[must_use]
+    pub fn matches(&self, value: &str) -> bool {
+        let characters = value.chars().collect::<Vec<_>>();
+        let (mut token, mut character) = (0, 0);
+        // The position of the most recent `*` and the character that it is currently matched up to.
+        let mut backtrack = None;
+
+        while character < characters.len() {
+            match self.tokens.get(token) {
+                Some(Token::AnySequence) => {
+                    backtrack = Some((token, character));
+                    token += 1;
+                }
+                Some(expected) if expected.matches(characters[character]) => {
+                    token += 1;
+                    character += 1;
+                }
+                _ => match backtrack {
+                    Some((star, matched)) => {
+                        backtrack = Some((star, matched + 1));
+                        token = star + 1;
+                        character = matched + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        self.tokens[token..].iter().all(|token| *token == Token::AnySequence)
+    }
+}
+
+/// Parses a character class following its opening `[`, up to and including its closing `]`.
+///
+/// Returns `None` if the class is not closed.
+fn class(characters: &mut Chars<'_>) -> Option<Token> {
+    let negated = characters.as_str().starts_with('!');
+
+    if negated {
+        characters.next();
+    }
+
+    // Each character within the class, and whether it was escaped.
+    let mut body = Vec::new();
+
+    loop {
+        match characters.next()? {
+            // A leading `]` is part of the class rather than closing it.
+            ']' if !body.is_empty() => break,
+            '\\' => body.push((characters.next()?, true)),
+            c => body.push((c, false)),
+        }
+    }
+
+    let mut ranges = Vec::new();
+    let mut index = 0;
+
+    while index < body.len() {
+        if let Some(&[(start, _), ('-', false), (end, _)]) = body.get(index..index + 3) {
+            ranges.push((start, end));
+            index += 3;
+        } else {
+            ranges.push((body[index].0, body[index].0));
+            index += 1;
+        }
+    }
+
+    Some(Token::Class { negated, ranges: ranges.into_boxed_slice() })
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Validator for Pattern {
+    fn validate(&self, value: &str) -> Result<(), String> {
+        if self.matches(value) { Ok(()) } else { Err(format!("does not match the pattern `{self}`")) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pattern, Token, Validator};
+    use crate::Error;
+
+    /// Compiles the given pattern, panicking if it is invalid.
+    fn pattern(source: &str) -> Pattern {
+        Pattern::new(source).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Returns the ranges of the single character class that makes up the given pattern.
+    fn class(source: &str) -> (bool, Vec<(char, char)>) {
+        match &*pattern(source).tokens {
+            [Token::Class { negated, ranges }] => (*negated, ranges.to_vec()),
+            tokens => panic!("expected a single class, found {tokens:?}"),
+        }
+    }
+
+    #[test]
+    fn classes_are_parsed() {
+        assert_eq!(class("[a-z_]"), (false, vec![('a', 'z'), ('_', '_')]));
+        assert_eq!(class("[!0-9]"), (true, vec![('0', '9')]));
+        assert_eq!(class("[]a]"), (false, vec![(']', ']'), ('a', 'a')]));
+        assert_eq!(class("[!]]"), (true, vec![(']', ']')]));
+        assert_eq!(class("[a-]"), (false, vec![('a', 'a'), ('-', '-')]));
+        assert_eq!(class("[-a]"), (false, vec![('-', '-'), ('a', 'a')]));
+        assert_eq!(class(r"[\]]"), (false, vec![(']', ']')]));
+        assert_eq!(class(r"[a\-z]"), (false, vec![('a', 'a'), ('-', '-'), ('z', 'z')]));
+        assert_eq!(class(r"[\\]"), (false, vec![('\\', '\\')]));
+        assert_eq!(class(r"[\!a]"), (false, vec![('!', '!'), ('a', 'a')]));
+        assert_eq!(class(r"[\a-\z]"), (false, vec![('a', 'z')]));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        for source in ["[", "[a", "[]", "[!]", r"[a\]", r"[\", "a\\"] {
+            assert!(matches!(Pattern::new(source), Err(Error::InvalidPattern { .. })), "{source}");
+        }
+    }
+
+    #[test]
+    fn escapes_match_literally() {
+        assert!(pattern(r"[\]]x").matches("]x"));
+        assert!(!pattern(r"[\]]x").matches(r"\]x"));
+        assert!(pattern(r"\*\?\[").matches("*?["));
+        assert!(!pattern(r"\*").matches("a"));
+        assert!(pattern(r"[a\-z]").matches("-"));
+        assert!(!pattern(r"[a\-z]").matches("b"));
+    }
+
+    #[test]
+    fn wildcards_backtrack() {
+        let cases = [
+            ("*", "", true),
+            ("*", "anything", true),
+            ("a*", "a", true),
+            ("*a", "ba", true),
+            ("*a", "ab", false),
+            ("a*b*c", "aXbYc", true),
+            ("a*b*c", "abcbc", true),
+            ("a*b*c", "acb", false),
+            ("*ab*", "aab", true),
+            ("*aab", "aaab", true),
+            ("a?c", "abc", true),
+            ("a?c", "ac", false),
+            ("??", "☃☃", true),
+            ("**a", "ba", true),
+            ("*[0-9]", "v10", true),
+            ("*[!0-9]", "v10", false),
+        ];
+
+        for (source, value, expected) in cases {
+            assert_eq!(pattern(source).matches(value), expected, "{source} against {value}");
+        }
+    }
+
+    #[test]
+    fn validation_reports_the_pattern() {
+        assert_eq!(pattern("v*").validate("v1"), Ok(()));
+        assert_eq!(pattern("v*").validate("x"), Err("does not match the pattern `v*`".into()));
+    }
+}
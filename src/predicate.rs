@@ -0,0 +1,583 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements parsing and evaluation of `cfg` predicates.
+//!
+//! Predicates use the same syntax as the `#[cfg(...)]` attribute, optionally wrapped in `cfg(...)`:
+//!
+//! ```
+//! use fig::Predicate;
+//!
+//! let predicate: Predicate = r#"all(unix, any(target_arch = "x86_64", target_arch = "aarch64"))"#.parse()?;
+//!
+//! assert_eq!(predicate, Predicate::all([
+//!     Predicate::flag("unix"),
+//!     Predicate::any([Predicate::eq("target_arch", "x86_64"), Predicate::eq("target_arch", "aarch64")]),
+//! ]));
+//! # Ok::<(), fig::Error>(())
+//! ```
+
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::{Error, Target};
+
+/// A `cfg` predicate.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Predicate {
+    /// A literal `true` or `false`.
+    Literal(bool),
+    /// A configuration that is set without a value, such as `unix`.
+    Flag(Box<str>),
+    /// A configuration that is set to the given value, such as `target_os = "linux"`.
+    Eq(Box<str>, Box<str>),
+    /// Holds if every inner predicate holds.
+    All(Box<[Self]>),
+    /// Holds if any inner predicate holds.
+    Any(Box<[Self]>),
+    /// Holds if the inner predicate does not hold.
+    Not(Box<Self>),
+}
+
+impl Predicate {
+    /// Creates a predicate that holds if the given configuration is set without a value.
+    pub fn flag(key: impl Into<Box<str>>) -> Self {
+        Self::Flag(key.into())
+    }
+
+    /// Creates a predicate that holds if the given configuration is set to the given value.
+    pub fn eq(key: impl Into<Box<str>>, value: impl Into<Box<str>>) -> Self {
+        Self::Eq(key.into(), value.into())
+    }
+
+    /// Creates a predicate that holds if every given predicate holds.
+    pub fn all(predicates: impl IntoIterator<Item = Self>) -> Self {
+        Self::All(predicates.into_iter().collect())
+    }
+
+    /// Creates a predicate that holds if any given predicate holds.
+    pub fn any(predicates: impl IntoIterator<Item = Self>) -> Self {
+        Self::Any(predicates.into_iter().collect())
+    }
+
+    /// Creates a predicate that holds if the given predicate does not hold.
+    #[expect(clippy::should_implement_trait, reason = "this mirrors the `not(...)` predicate syntax")]
+    #[must_use]
+    pub fn not(predicate: Self) -> Self {
+        Self::Not(Box::new(predicate))
+    }
+
+    /// Parses a predicate from the given string.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the string is not a valid predicate.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        Parser::new(source).parse()
+    }
+
+    /// Evaluates this predicate using the given function, which returns `true` if a configuration is set to a value.
+    pub fn evaluate_with<F>(&self, is_set: &mut F) -> bool
+    where
+        F: FnMut(&str, Option<&str>) -> bool,
+    {
+        match self {
+            Self::Literal(value) => *value,
+            Self::Flag(key) => is_set(key, None),
+            Self::Eq(key, value) => is_set(key, Some(value)),
+            Self::All(predicates) => predicates.iter().all(|predicate| predicate.evaluate_with(is_set)),
+            Self::Any(predicates) => predicates.iter().any(|predicate| predicate.evaluate_with(is_set)),
+            Self::Not(predicate) => !predicate.evaluate_with(is_set),
+        }
+    }
+
+    /// Evaluates this predicate against the given target, the enabled Cargo features, and every configuration that
+    /// has been set on the current thread.
+    #[must_use]
+    pub fn evaluate(&self, target: &Target) -> bool {
+        self.evaluate_with(&mut |key, value| match (key, value) {
+            ("feature", Some(feature)) => crate::env::has_feature(feature) || target.has_value(key, feature),
+            (key, None) => target.is_flag(key) || crate::sink::is_assigned(key, None),
+            (key, Some(value)) => target.has_value(key, value) || crate::sink::is_assigned(key, Some(value)),
+        })
+    }
+
+    /// Evaluates this predicate against the current target, as detected by [`Target::detect`].
+    ///
+    /// The target is only detected once per thread, and is reused by later evaluations.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the target could not be detected.
+    #[must_use]
+    pub fn evaluate_current(&self) -> bool {
+        self.try_evaluate_current().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Evaluates this predicate against the current target, as detected by [`Target::try_detect`].
+    ///
+    /// The target is only detected once per thread, and is reused by later evaluations.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the target could not be detected.
+    pub fn try_evaluate_current(&self) -> Result<bool, Error> {
+        Target::current().map(|target| self.evaluate(&target))
+    }
+}
+
+impl FromStr for Predicate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// Writes the given predicates separated by commas.
+        fn list(f: &mut std::fmt::Formatter<'_>, name: &str, predicates: &[Predicate]) -> std::fmt::Result {
+            write!(f, "{name}(")?;
+
+            for (index, predicate) in predicates.iter().enumerate() {
+                if index != 0 {
+                    f.write_str(", ")?;
+                }
+
+                predicate.fmt(f)?;
+            }
+
+            f.write_str(")")
+        }
+
+        match self {
+            Self::Literal(value) => write!(f, "{value}"),
+            Self::Flag(key) if matches!(&**key, "true" | "false") => write!(f, "r#{key}"),
+            Self::Flag(key) => f.write_str(key),
+            Self::Eq(key, value) => write!(f, "{key} = {}", crate::directive::to_str_literal(value)),
+            Self::All(predicates) => list(f, "all", predicates),
+            Self::Any(predicates) => list(f, "any", predicates),
+            Self::Not(predicate) => write!(f, "not({predicate})"),
+        }
+    }
+}
+
+/// A token within a predicate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token<'s> {
+    /// An identifier, such as `unix` or `r#true`.
+    Ident(&'s str),
+    /// An unescaped string literal.
+    Str(Box<str>),
+    /// An opening parenthesis.
+    Open,
+    /// A closing parenthesis.
+    Close,
+    /// A comma.
+    Comma,
+    /// An equals sign.
+    Eq,
+    /// The end of the input.
+    End,
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(ident) => write!(f, "`{ident}`"),
+            Self::Str(_) => f.write_str("a string literal"),
+            Self::Open => f.write_str("`(`"),
+            Self::Close => f.write_str("`)`"),
+            Self::Comma => f.write_str("`,`"),
+            Self::Eq => f.write_str("`=`"),
+            Self::End => f.write_str("the end of the predicate"),
+        }
+    }
+}
+
+/// A recursive descent parser for predicates.
+struct Parser<'s> {
+    /// The source string.
+    source: &'s str,
+    /// The byte offset of the next character.
+    offset: usize,
+}
+
+impl<'s> Parser<'s> {
+    /// Creates a new [`Parser`] for the given source string.
+    const fn new(source: &'s str) -> Self {
+        Self { source, offset: 0 }
+    }
+
+    /// Parses the entire source string as a single predicate, which may be wrapped in `cfg(...)`.
+    fn parse(mut self) -> Result<Predicate, Error> {
+        let checkpoint = self.offset;
+        let predicate = match self.next()? {
+            (Token::Ident("cfg"), _) if self.peek()?.0 == Token::Open => {
+                self.expect(&Token::Open)?;
+
+                let predicate = self.predicate()?;
+
+                self.expect(&Token::Close)?;
+
+                predicate
+            }
+            _ => {
+                self.offset = checkpoint;
+
+                self.predicate()?
+            }
+        };
+
+        self.expect(&Token::End)?;
+
+        Ok(predicate)
+    }
+
+    /// Parses a single predicate.
+    fn predicate(&mut self) -> Result<Predicate, Error> {
+        let (ident, span) = match self.next()? {
+            (Token::Ident(ident), span) => (ident, span),
+            (token, span) => return Err(self.error(span, format!("expected a predicate, found {token}"))),
+        };
+
+        match (ident, self.peek()?.0) {
+            ("all" | "any" | "not", Token::Open) => {
+                self.expect(&Token::Open)?;
+
+                let predicates = self.list()?;
+
+                match ident {
+                    "all" => Ok(Predicate::All(predicates.into_boxed_slice())),
+                    "any" => Ok(Predicate::Any(predicates.into_boxed_slice())),
+                    _ => match <[Predicate; 1]>::try_from(predicates) {
+                        Ok([predicate]) => Ok(Predicate::not(predicate)),
+                        Err(_) => Err(self.error(span.start..self.offset, "`not` expects exactly one predicate")),
+                    },
+                }
+            }
+            (_, Token::Open) => Err(self.error(span, format!("unknown predicate `{ident}`"))),
+            ("true", _) => Ok(Predicate::Literal(true)),
+            ("false", _) => Ok(Predicate::Literal(false)),
+            (_, Token::Eq) => {
+                self.expect(&Token::Eq)?;
+
+                match self.next()? {
+                    (Token::Str(value), _) => Ok(Predicate::Eq(Self::unraw(ident).into(), value)),
+                    (token, span) => Err(self.error(span, format!("expected a string literal, found {token}"))),
+                }
+            }
+            (_, _) => Ok(Predicate::Flag(Self::unraw(ident).into())),
+        }
+    }
+
+    /// Strips the `r#` prefix from the given identifier, if present.
+    fn unraw(ident: &str) -> &str {
+        ident.strip_prefix("r#").unwrap_or(ident)
+    }
+
+    /// Parses a comma-separated list of predicates, up to and including the closing parenthesis.
+    fn list(&mut self) -> Result<Vec<Predicate>, Error> {
+        let mut predicates = Vec::new();
+
+        loop {
+            if self.peek()?.0 == Token::Close {
+                self.next()?;
+
+                return Ok(predicates);
+            }
+
+            predicates.push(self.predicate()?);
+
+            match self.next()? {
+                (Token::Comma, _) => {}
+                (Token::Close, _) => return Ok(predicates),
+                (token, span) => return Err(self.error(span, format!("expected `,` or `)`, found {token}"))),
+            }
+        }
+    }
+
+    /// Consumes the next token, returning an error if it is not the expected token.
+    fn expect(&mut self, expected: &Token<'_>) -> Result<(), Error> {
+        match self.next()? {
+            (token, _) if token == *expected => Ok(()),
+            (token, span) => Err(self.error(span, format!("expected {expected}, found {token}"))),
+        }
+    }
+
+    /// Returns the next token without consuming it.
+    fn peek(&mut self) -> Result<(Token<'s>, Range<usize>), Error> {
+        let checkpoint = self.offset;
+        let token = self.next();
+
+        self.offset = checkpoint;
+
+        token
+    }
+
+    /// Consumes and returns the next token and its span.
+    fn next(&mut self) -> Result<(Token<'s>, Range<usize>), Error> {
+        let rest = &self.source[self.offset..];
+
+        self.offset += rest.len() - rest.trim_start().len();
+
+        let start = self.offset;
+        let mut characters = self.source[start..].chars();
+        let token = match characters.next() {
+            None => Token::End,
+            Some('(') => Token::Open,
+            Some(')') => Token::Close,
+            Some(',') => Token::Comma,
+            Some('=') => Token::Eq,
+            Some('"') => return self.string(start),
+            Some(c) if c == '_' || unicode_ident::is_xid_start(c) => return self.ident(start),
+            Some(c) => return Err(self.error(start..start + c.len_utf8(), format!("unexpected character {c:?}"))),
+        };
+
+        self.offset += self.source[start..].len() - characters.as_str().len();
+
+        Ok((token, start..self.offset))
+    }
+
+    /// Consumes an identifier starting at the given offset, which may be a raw identifier.
+    fn ident(&mut self, start: usize) -> Result<(Token<'s>, Range<usize>), Error> {
+        let rest = &self.source[start..];
+        let prefix = if rest.starts_with("r#") { 2 } else { 0 };
+        let length = rest[prefix..].find(|c| !unicode_ident::is_xid_continue(c)).unwrap_or(rest.len() - prefix);
+
+        self.offset = start + prefix + length;
+
+        let ident = &self.source[start..self.offset];
+
+        if prefix != 0 {
+            crate::ident::validate(ident)
+                .map_err(|reason| self.error(start..self.offset, format!("invalid raw identifier: {reason}")))?;
+        }
+
+        Ok((Token::Ident(ident), start..self.offset))
+    }
+
+    /// Consumes and unescapes a string literal starting at the given offset.
+    fn string(&mut self, start: usize) -> Result<(Token<'s>, Range<usize>), Error> {
+        let mut value = String::new();
+        let mut characters = self.source[start + 1..].char_indices().map(|(index, c)| (start + 1 + index, c));
+
+        while let Some((index, character)) = characters.next() {
+            let escaped = match character {
+                '"' => {
+                    self.offset = index + 1;
+
+                    return Ok((Token::Str(value.into_boxed_str()), start..self.offset));
+                }
+                '\\' => characters.next().map(|(_, c)| c),
+                c => {
+                    value.push(c);
+
+                    continue;
+                }
+            };
+
+            let unescaped = match escaped {
+                Some('"') => '"',
+                Some('\'') => '\'',
+                Some('\\') => '\\',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some('x') => {
+                    let code = self
+                        .source
+                        .get(index + 2..index + 4)
+                        .filter(|code| code.bytes().all(|b| b.is_ascii_hexdigit()));
+                    let character = code.and_then(|code| u8::from_str_radix(code, 16).ok()).filter(u8::is_ascii);
+
+                    match character {
+                        Some(character) => {
+                            characters.nth(1);
+
+                            char::from(character)
+                        }
+                        None => return Err(self.error(index..index + 2, "invalid ascii escape")),
+                    }
+                }
+                Some('u') => {
+                    let rest = &self.source[index + 2..];
+                    let code = rest.strip_prefix('{').and_then(|rest| Some(&rest[..rest.find('}')?]));
+                    // Like Rust, up to six hex digits are accepted, which may be separated by underscores.
+                    let digits = code
+                        .filter(|code| code.starts_with(|c: char| c.is_ascii_hexdigit()))
+                        .filter(|code| code.chars().all(|c| c.is_ascii_hexdigit() || c == '_'))
+                        .map(|code| code.replace('_', ""))
+                        .filter(|digits| digits.len() <= 6);
+                    let character =
+                        digits.and_then(|digits| u32::from_str_radix(&digits, 16).ok()).and_then(char::from_u32);
+
+                    match (code, character) {
+                        (Some(code), Some(character)) => {
+                            characters.nth(code.len() + 1);
+
+                            character
+                        }
+                        _ => return Err(self.error(index..index + 2, "invalid unicode escape")),
+                    }
+                }
+                Some(_) => return Err(self.error(index..index + 2, "unknown character escape")),
+                None => break,
+            };
+
+            value.push(unescaped);
+        }
+
+        Err(self.error(start..self.source.len(), "unterminated string literal"))
+    }
+
+    /// Returns an error that points to the given span of the source string.
+    fn error(&self, span: Range<usize>, message: impl Into<Box<str>>) -> Error {
+        Error::InvalidPredicate { predicate: self.source.into(), span, message: message.into() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::Predicate;
+    use crate::testing::Harness;
+    use crate::{Directive, Error, Target};
+
+    /// Parses the given source, panicking if it is invalid.
+    fn parse(source: &str) -> Predicate {
+        Predicate::parse(source).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Returns the span and message of the error produced by parsing the given source.
+    fn error(source: &str) -> (Range<usize>, String) {
+        match Predicate::parse(source) {
+            Err(Error::InvalidPredicate { span, message, .. }) => (span, message.into_string()),
+            other => panic!("expected an invalid predicate, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn predicates_are_parsed() {
+        assert_eq!(parse("unix"), Predicate::flag("unix"));
+        assert_eq!(parse(" cfg( unix ) "), Predicate::flag("unix"));
+        assert_eq!(parse("true"), Predicate::Literal(true));
+        assert_eq!(parse("r#true"), Predicate::flag("true"));
+        assert_eq!(parse("r#_private"), Predicate::flag("_private"));
+        assert_eq!(parse(r#"target_os = "linux""#), Predicate::eq("target_os", "linux"));
+        assert_eq!(parse("all()"), Predicate::all([]));
+        assert_eq!(parse("any(unix, windows,)"), Predicate::any([Predicate::flag("unix"), Predicate::flag("windows")]));
+        assert_eq!(parse("not(all(unix))"), Predicate::not(Predicate::all([Predicate::flag("unix")])));
+        assert_eq!(parse("cfg"), Predicate::flag("cfg"));
+    }
+
+    #[test]
+    fn strings_are_unescaped() {
+        let cases = [
+            (r#""a\"b""#, "a\"b"),
+            (r#""a\\b""#, "a\\b"),
+            (r#""\n\r\t\0\'""#, "\n\r\t\0'"),
+            (r#""\x41\x7f""#, "A\u{7f}"),
+            (r#""\u{2603}\u{1F600}""#, "\u{2603}\u{1F600}"),
+            (r#""\u{1_F600}\u{0000_41}\u{4__1_}""#, "\u{1F600}AA"),
+            (r#""☃""#, "☃"),
+        ];
+
+        for (literal, value) in cases {
+            assert_eq!(parse(&format!("key = {literal}")), Predicate::eq("key", value), "{literal}");
+        }
+    }
+
+    #[test]
+    fn predicates_round_trip_through_display() {
+        let predicates = [
+            r#"all(unix, any(target_os = "linux", not(windows)), r#true, false)"#,
+            r#"key = "quote \" backslash \\ newline \n""#,
+            "any()",
+        ];
+
+        for source in predicates {
+            let predicate = parse(source);
+
+            assert_eq!(parse(&predicate.to_string()), predicate, "{source}");
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        assert_eq!(error(""), (0..0, "expected a predicate, found the end of the predicate".into()));
+        assert_eq!(error("unix windows"), (5..12, "expected the end of the predicate, found `windows`".into()));
+        assert_eq!(error("some(unix)"), (0..4, "unknown predicate `some`".into()));
+        assert_eq!(error("not(unix, windows)"), (0..18, "`not` expects exactly one predicate".into()));
+        assert_eq!(error("all(unix windows)"), (9..16, "expected `,` or `)`, found `windows`".into()));
+        assert_eq!(error("key = value"), (6..11, "expected a string literal, found `value`".into()));
+        assert_eq!(error("unix & windows"), (5..6, "unexpected character '&'".into()));
+        assert_eq!(error(r#"key = "open"#), (6..11, "unterminated string literal".into()));
+        assert_eq!(error(r#"key = "\q""#), (7..9, "unknown character escape".into()));
+        assert_eq!(error(r#"key = "\x80""#), (7..9, "invalid ascii escape".into()));
+        assert_eq!(error(r#"key = "\x+1""#), (7..9, "invalid ascii escape".into()));
+        assert_eq!(error(r#"key = "\u{110000}""#), (7..9, "invalid unicode escape".into()));
+        assert_eq!(error(r#"key = "\u{+41}""#), (7..9, "invalid unicode escape".into()));
+        assert_eq!(error(r#"key = "\u{_41}""#), (7..9, "invalid unicode escape".into()));
+        assert_eq!(error(r#"key = "\u{}""#), (7..9, "invalid unicode escape".into()));
+        assert_eq!(error(r#"key = "\u{0000041}""#), (7..9, "invalid unicode escape".into()));
+        assert_eq!(error("cfg(unix"), (8..8, "expected `)`, found the end of the predicate".into()));
+    }
+
+    #[test]
+    fn raw_identifiers_are_validated() {
+        assert_eq!(error("r#").0, 0..2);
+        assert_eq!(error("r#1abc").0, 0..6);
+        assert_eq!(error("all(r#self)").0, 4..10);
+        assert!(error("r#_").1.starts_with("invalid raw identifier"));
+    }
+
+    #[test]
+    fn flags_do_not_match_valued_keys() {
+        let harness = Harness::new()
+            .env("CARGO_CFG_TARGET_OS", "linux")
+            .env("CARGO_CFG_TARGET_ABI", "")
+            .env("CARGO_CFG_UNIX", "");
+        let target = harness.run(Target::try_detect).0.unwrap_or_else(|error| panic!("{error}"));
+
+        assert!(parse("unix").evaluate(&target));
+        assert!(parse(r#"target_os = "linux""#).evaluate(&target));
+        assert!(!parse("target_os").evaluate(&target));
+        assert!(!parse("windows").evaluate(&target));
+        assert!(parse(r#"not(any(windows, target_os = "macos"))"#).evaluate(&target));
+        // rustc sets `target_abi = ""` on targets without an ABI, rather than setting `target_abi` as a flag.
+        assert!(parse(r#"target_abi = """#).evaluate(&target));
+        assert!(!parse("target_abi").evaluate(&target));
+    }
+
+    #[test]
+    fn the_target_is_detected_once() {
+        let (result, directives) = Harness::new().env("CARGO_CFG_UNIX", "").run(|| {
+            let first = parse("unix").try_evaluate_current()?;
+            let second = parse("windows").try_evaluate_current()?;
+
+            Ok::<_, Error>((first, second))
+        });
+        let reruns = directives.iter().filter(|directive| matches!(directive, Directive::RerunIfEnvChanged { .. }));
+
+        assert_eq!(result.ok(), Some((true, false)));
+        assert_eq!(reruns.count(), 1);
+    }
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements compiling small snippets of code to detect what the compiler supports.
+
+use std::ffi::OsString;
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Error;
+
+/// The edition that probes are compiled with, which is the latest edition supported by every compiler since 1.56.
+const EDITION: &str = "2021";
+
+/// The number of probes that have been compiled, used to give each probe a unique crate name.
+static PROBES: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns `true` if the given expression compiles.
+pub fn expr(expr: &str) -> Result<bool, Error> {
+    self::compiles(&format!("pub fn probe() {{ let _ = {expr}; }}"))
+}
+
+/// Returns `true` if the given type compiles.
+pub fn ty(ty: &str) -> Result<bool, Error> {
+    self::compiles(&format!("pub type Probe = {ty};"))
+}
+
+/// Returns `true` if the given path can be imported.
+pub fn path(path: &str) -> Result<bool, Error> {
+    self::compiles(&format!("pub use {path};"))
+}
+
+/// Returns `true` if the given library source compiles for the current target.
+///
+/// The source is compiled with the compiler used by the current build into `OUT_DIR`, using the same flags as Cargo and
+/// the 2021 edition.
+pub fn compiles(source: &str) -> Result<bool, Error> {
+    let variable = |key| crate::env::var_os(key).filter(|value| !value.is_empty());
+    let out_dir = variable("OUT_DIR").ok_or_else(|| Error::MissingVariable { variable: "OUT_DIR".into() })?;
+    let id = PROBES.fetch_add(1, Ordering::Relaxed);
+
+    let mut command = crate::rustc::command();
+
+    command.args(["--crate-name", &format!("fig_probe_{id}"), "--crate-type=lib", "--emit=metadata"]);
+    command.arg(format!("--edition={EDITION}"));
+    command.arg("--out-dir").arg(out_dir);
+
+    if let Some(target) = variable("TARGET").filter(|target| Some(target) != variable("HOST").as_ref()) {
+        command.arg("--target").arg(target);
+    }
+
+    if let Some(flags) = variable("CARGO_ENCODED_RUSTFLAGS") {
+        let flags = flags
+            .into_string()
+            .map_err(|_| Error::NonUnicodeVariable { variable: "CARGO_ENCODED_RUSTFLAGS".into() })?;
+
+        command.args(flags.split('\x1f').map(OsString::from));
+    }
+
+    command.arg("-").stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null());
+
+    let program = command.get_program().to_string_lossy().into_owned().into_boxed_str();
+    let error = |source| Error::Command { program: program.clone(), source };
+    let mut child = command.spawn().map_err(error)?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(format!("#![allow(warnings)]\n{source}\n").as_bytes()).map_err(error)?;
+    }
+
+    Ok(child.wait().map_err(error)?.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::Harness;
+
+    #[test]
+    fn probes_use_a_modern_edition() {
+        let out_dir = std::env::temp_dir().join(format!("fig-probe-test-{}", std::process::id()));
+
+        std::fs::create_dir_all(&out_dir).unwrap_or_else(|error| panic!("{error}"));
+
+        let harness = Harness::new().env("OUT_DIR", &out_dir);
+        let (result, _) = harness.run(|| Ok::<_, crate::Error>((super::expr("async {}")?, super::expr("1 +")?)));
+
+        drop(std::fs::remove_dir_all(&out_dir));
+
+        assert_eq!(result.ok(), Some((true, false)));
+    }
+}
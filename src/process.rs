@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements running external programs and capturing their output.
+
+use std::process::Command;
+
+use crate::Error;
+
+/// Runs the given command to completion, returning its standard output.
+///
+/// Commands that exit unsuccessfully are reported as errors, including their standard error output.
+pub fn run(command: &mut Command) -> Result<String, Error> {
+    let program = command.get_program().to_string_lossy().into_owned().into_boxed_str();
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(source) => return Err(Error::Command { program, source }),
+    };
+
+    if !output.status.success() {
+        let message = format!("{}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim());
+
+        return Err(Error::Command { program, source: std::io::Error::other(message) });
+    }
+
+    String::from_utf8(output.stdout).map_err(|error| Error::Command {
+        program,
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+    })
+}
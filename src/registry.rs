@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements a registry that collects, validates, and batches declarations before emitting them.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::directive::Values;
+use crate::sink::Sink;
+use crate::{Directive, Error};
+
+/// Collects the directives emitted while it is active, emitting them all at once when finished.
+///
+/// Declaring the same key twice merges compatible value lists into a single declaration, which accepts no value if
+/// either declaration does. Conflicting declarations or assignments are reported as errors at the point that they are
+/// made.
+///
+/// ```no_run
+/// use fig::{Cfg, CheckedCfg, Registry};
+///
+/// let mut registry = Registry::new();
+///
+/// registry.collect(|| {
+///     Cfg::new("backend").assigned_one_of(&["epoll"]).set(Some("epoll"));
+///     let _ = Cfg::new("backend").assigned_one_of(&["kqueue"]);
+/// });
+///
+/// // Emits `cargo::rustc-check-cfg=cfg(backend, values("epoll", "kqueue"))` and `cargo::rustc-cfg=backend="epoll"`.
+/// registry.finish();
+/// ```
+#[must_use = "this value does nothing unless finished"]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Registry {
+    /// The declared configurations, keyed by name.
+    declarations: BTreeMap<Box<str>, Values>,
+    /// The assigned configurations, keyed by name. Only multi-valued configurations may have several values.
+    assignments: BTreeMap<Box<str>, BTreeSet<Option<Box<str>>>>,
+    /// The environment variables that the build script depends on.
+    variables: BTreeSet<Box<str>>,
+    /// The files that the build script depends on.
+    files: BTreeSet<Box<Path>>,
+}
+
+impl Registry {
+    /// Creates a new empty [`Registry`].
+    pub const fn new() -> Self {
+        Self {
+            declarations: BTreeMap::new(),
+            assignments: BTreeMap::new(),
+            variables: BTreeSet::new(),
+            files: BTreeSet::new(),
+        }
+    }
+
+    /// Collects every directive emitted by the given function into this registry.
+    pub fn collect<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let (output, registry) = crate::sink::with_sink(std::mem::take(self), f);
+
+        *self = registry;
+
+        output
+    }
+
+    /// Emits every collected directive to the current sink, sorted by key.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a directive could not be emitted.
+    pub fn finish(self) {
+        self.try_finish().unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Emits every collected directive to the current sink, sorted by key.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a directive could not be emitted.
+    pub fn try_finish(self) -> Result<(), Error> {
+        for (key, values) in self.declarations {
+            crate::sink::emit(Directive::CheckCfg { key, values })?;
+        }
+        for variable in self.variables {
+            crate::sink::emit(Directive::RerunIfEnvChanged { variable })?;
+        }
+        for path in self.files {
+            crate::sink::emit(Directive::RerunIfChanged { path })?;
+        }
+        for (key, values) in self.assignments {
+            for value in values {
+                crate::sink::emit(Directive::Cfg { key: key.clone(), value })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merges the given declaration into any existing declaration of the same key.
+    fn declare(&mut self, key: Box<str>, values: Values) -> Result<(), Error> {
+        /// Appends every value in `new` that is not already within `old`.
+        fn union(old: &[Box<str>], new: &[Box<str>]) -> Box<[Box<str>]> {
+            old.iter().chain(new.iter().filter(|v| !old.contains(v))).cloned().collect()
+        }
+
+        let Some(previous) = self.declarations.get_mut(&key) else {
+            self.declarations.insert(key, values);
+
+            return Ok(());
+        };
+
+        *previous = match (&*previous, values) {
+            (Values::None, Values::None) => Values::None,
+            (Values::Any, Values::Any) => Values::Any,
+            (Values::OneOf(old), Values::OneOf(new)) => Values::OneOf(union(old, &new)),
+            (Values::NoneOrOneOf(old), Values::NoneOrOneOf(new) | Values::OneOf(new))
+            | (Values::OneOf(old), Values::NoneOrOneOf(new)) => Values::NoneOrOneOf(union(old, &new)),
+            (Values::None, Values::OneOf(new) | Values::NoneOrOneOf(new)) => Values::NoneOrOneOf(new),
+            (Values::OneOf(old) | Values::NoneOrOneOf(old), Values::None) => Values::NoneOrOneOf(old.clone()),
+            (Values::AnyOf(old), Values::AnyOf(new)) => Values::AnyOf(union(old, &new)),
+            (_, values) => {
+                return Err(Error::ConflictingDeclaration { key, previous: previous.clone(), values });
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Records the given assignment, rejecting it if the key was already assigned a different value.
+    ///
+    /// Configurations declared with [`Values::AnyOf`] may be assigned several values.
+    fn assign(&mut self, key: Box<str>, value: Option<Box<str>>) -> Result<(), Error> {
+        let multiple = matches!(self.declarations.get(&key), Some(Values::AnyOf(_)));
+
+        match self.assignments.get_mut(&key) {
+            Some(values) if multiple => {
+                values.insert(value);
+
+                Ok(())
+            }
+            Some(values) => match values.first() {
+                Some(previous) if *previous != value => {
+                    Err(Error::ConflictingAssignment { key, previous: previous.clone(), value })
+                }
+                _ => Ok(()),
+            },
+            None => {
+                self.assignments.insert(key, BTreeSet::from([value]));
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Sink for Registry {
+    fn emit(&mut self, directive: Directive) -> Result<(), Error> {
+        match directive {
+            Directive::CheckCfg { key, values } => self.declare(key, values),
+            Directive::Cfg { key, value } => self.assign(key, value),
+            Directive::RerunIfEnvChanged { variable } => {
+                self.variables.insert(variable);
+
+                Ok(())
+            }
+            Directive::RerunIfChanged { path } => {
+                self.files.insert(path);
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Registry;
+    use crate::directive::Values;
+    use crate::sink::{Buffer, Sink};
+    use crate::{Directive, Error};
+
+    /// Returns a list of values from the given strings.
+    fn list(values: &[&str]) -> Box<[Box<str>]> {
+        values.iter().map(|&v| v.into()).collect()
+    }
+
+    /// Declares the given values for `key` in order, returning the merged declaration.
+    fn merge(values: impl IntoIterator<Item = Values>) -> Result<Values, Error> {
+        let mut registry = Registry::new();
+
+        for values in values {
+            registry.emit(Directive::CheckCfg { key: "key".into(), values })?;
+        }
+
+        Ok(registry.declarations.remove("key").expect("the key should be declared"))
+    }
+
+    #[test]
+    fn compatible_declarations_merge() {
+        let one_of = |values| Values::OneOf(list(values));
+        let none_or_one_of = |values| Values::NoneOrOneOf(list(values));
+
+        assert_eq!(merge([one_of(&["a"]), one_of(&["b", "a"])]).ok(), Some(one_of(&["a", "b"])));
+        assert_eq!(merge([one_of(&["a"]), none_or_one_of(&["b"])]).ok(), Some(none_or_one_of(&["a", "b"])));
+        assert_eq!(merge([none_or_one_of(&["a"]), one_of(&["b"])]).ok(), Some(none_or_one_of(&["a", "b"])));
+        assert_eq!(merge([Values::None, one_of(&["a"])]).ok(), Some(none_or_one_of(&["a"])));
+        assert_eq!(merge([one_of(&["a"]), Values::None]).ok(), Some(none_or_one_of(&["a"])));
+        assert_eq!(merge([Values::Any, Values::Any]).ok(), Some(Values::Any));
+        assert_eq!(
+            merge([Values::AnyOf(list(&["a"])), Values::AnyOf(list(&["b"]))]).ok(),
+            Some(Values::AnyOf(list(&["a", "b"])))
+        );
+    }
+
+    #[test]
+    fn incompatible_declarations_conflict() {
+        let conflicts = |values: [Values; 2]| matches!(merge(values), Err(Error::ConflictingDeclaration { .. }));
+
+        assert!(conflicts([Values::Any, Values::None]));
+        assert!(conflicts([Values::Any, Values::OneOf(list(&["a"]))]));
+        assert!(conflicts([Values::AnyOf(list(&["a"])), Values::OneOf(list(&["a"]))]));
+        assert!(conflicts([Values::None, Values::AnyOf(list(&["a"]))]));
+    }
+
+    #[test]
+    fn single_valued_assignments_conflict() -> Result<(), Error> {
+        let mut registry = Registry::new();
+
+        registry.emit(Directive::CheckCfg { key: "key".into(), values: Values::OneOf(list(&["a", "b"])) })?;
+        registry.emit(Directive::Cfg { key: "key".into(), value: Some("a".into()) })?;
+        registry.emit(Directive::Cfg { key: "key".into(), value: Some("a".into()) })?;
+
+        let result = registry.emit(Directive::Cfg { key: "key".into(), value: Some("b".into()) });
+
+        assert!(matches!(result, Err(Error::ConflictingAssignment { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn multi_valued_assignments_are_emitted_once_each() -> Result<(), Error> {
+        let mut registry = Registry::new();
+
+        registry.emit(Directive::CheckCfg { key: "key".into(), values: Values::AnyOf(list(&["a", "b"])) })?;
+
+        for value in ["b", "a", "b"] {
+            registry.emit(Directive::Cfg { key: "key".into(), value: Some(value.into()) })?;
+        }
+
+        let ((), buffer) = crate::sink::with_sink(Buffer::new(), || registry.finish());
+
+        assert_eq!(
+            buffer.into_directives(),
+            [
+                Directive::CheckCfg { key: "key".into(), values: Values::AnyOf(list(&["a", "b"])) },
+                Directive::Cfg { key: "key".into(), value: Some("a".into()) },
+                Directive::Cfg { key: "key".into(), value: Some("b".into()) },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements detection of the compiler's version and release channel.
+//!
+//! ```no_run
+//! use fig::rustc::Rustc;
+//!
+//! let rustc = Rustc::detect();
+//!
+//! // Declares `rustc_1_70` through `rustc_1_90`, setting those that the compiler satisfies, along with
+//! // `rustc_channel` and `rustc_date`.
+//! rustc.declare(70..=90);
+//! ```
+
+use std::ffi::OsString;
+use std::ops::RangeInclusive;
+use std::process::Command;
+
+use crate::version::{Channel, Version};
+use crate::{Cfg, CheckedCfg, Error};
+
+/// The key of the configuration that is set to the compiler's release channel.
+pub const CHANNEL_KEY: &str = "rustc_channel";
+/// The key of the configuration that is set to the compiler's commit date.
+pub const DATE_KEY: &str = "rustc_date";
+
+/// The version information reported by the compiler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rustc {
+    /// The release version.
+    version: Version,
+    /// The release channel.
+    channel: Channel,
+    /// The hash of the commit that the compiler was built from, if known.
+    commit_hash: Option<Box<str>>,
+    /// The date of the commit that the compiler was built from, formatted as `YYYY-MM-DD`, if known.
+    commit_date: Option<Box<str>>,
+    /// The target triple of the host, if known.
+    host: Option<Box<str>>,
+}
+
+impl Rustc {
+    /// Queries the compiler used by the current build.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the compiler could not be run, or its output could not be parsed.
+    #[must_use]
+    pub fn detect() -> Self {
+        Self::try_detect().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Queries the compiler used by the current build by running `rustc -vV`.
+    ///
+    /// The compiler is taken from `RUSTC`, and is run through `RUSTC_WORKSPACE_WRAPPER` or `RUSTC_WRAPPER` if either is
+    /// set, the same way that Cargo would.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the compiler could not be run, or its output could not be parsed.
+    pub fn try_detect() -> Result<Self, Error> {
+        Self::parse(&crate::process::run(self::command().arg("-vV"))?)
+    }
+
+    /// Parses the output of `rustc -vV`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the output does not contain a valid release.
+    pub fn parse(output: &str) -> Result<Self, Error> {
+        let mut release = None;
+        let mut rustc = Self {
+            version: Version::new(0, 0, 0),
+            channel: Channel::Stable,
+            commit_hash: None,
+            commit_date: None,
+            host: None,
+        };
+
+        for (key, value) in output.lines().filter_map(|line| line.split_once(": ")) {
+            let known = || Some(value.trim()).filter(|value| *value != "unknown").map(Into::into);
+
+            match key {
+                "release" => release = Some(value.trim()),
+                "commit-hash" => rustc.commit_hash = known(),
+                "commit-date" => rustc.commit_date = known(),
+                "host" => rustc.host = known(),
+                _ => {}
+            }
+        }
+
+        let release = release.ok_or_else(|| Error::InvalidVersion { version: output.trim().into() })?;
+        let (version, suffix) = release.split_once('-').unwrap_or((release, ""));
+
+        rustc.version = Version::parse(version)?;
+        rustc.channel =
+            Channel::from_suffix(suffix).ok_or_else(|| Error::InvalidVersion { version: release.into() })?;
+
+        Ok(rustc)
+    }
+
+    /// Returns the compiler's release version.
+    #[must_use]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the compiler's release channel.
+    #[must_use]
+    pub const fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    /// Returns the hash of the commit that the compiler was built from, if known.
+    #[must_use]
+    pub fn commit_hash(&self) -> Option<&str> {
+        self.commit_hash.as_deref()
+    }
+
+    /// Returns the date of the commit that the compiler was built from, formatted as `YYYY-MM-DD`, if known.
+    #[must_use]
+    pub fn commit_date(&self) -> Option<&str> {
+        self.commit_date.as_deref()
+    }
+
+    /// Returns the target triple of the host, if known.
+    #[must_use]
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Returns `true` if the compiler is at least the given version.
+    #[must_use]
+    pub fn is_at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        self.version >= Version::new(major, minor, patch)
+    }
+
+    /// Declares every version, channel, and date configuration, setting those that apply to this compiler.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a directive could not be emitted.
+    pub fn declare(&self, minors: RangeInclusive<u32>) {
+        self.try_declare(minors).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Declares every version, channel, and date configuration, setting those that apply to this compiler.
+    ///
+    /// See [`Rustc::try_declare_versions`], [`Rustc::try_declare_channel`], and [`Rustc::try_declare_date`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a directive could not be emitted.
+    pub fn try_declare(&self, minors: RangeInclusive<u32>) -> Result<(), Error> {
+        self.try_declare_versions(minors)?;
+        self.try_declare_channel()?;
+        self.try_declare_date()
+    }
+
+    /// Declares a `rustc_1_N` configuration for each given minor version, setting those that this compiler satisfies.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a directive could not be emitted.
+    pub fn declare_versions(&self, minors: RangeInclusive<u32>) {
+        self.try_declare_versions(minors).unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Declares a `rustc_1_N` configuration for each given minor version, setting those that this compiler satisfies.
+    ///
+    /// For example, a `1.80.1` compiler sets `rustc_1_79` and `rustc_1_80`, but not `rustc_1_81`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a directive could not be emitted.
+    pub fn try_declare_versions(&self, minors: RangeInclusive<u32>) -> Result<(), Error> {
+        for minor in minors {
+            let key = format!("rustc_1_{minor}");
+            let cfg = Cfg::try_new(&key)?.try_assigned_none()?;
+
+            if self.is_at_least(1, minor, 0) {
+                cfg.try_set(None)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Declares the `rustc_channel` configuration, setting it to this compiler's release channel.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a directive could not be emitted.
+    pub fn declare_channel(&self) {
+        self.try_declare_channel().unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Declares the `rustc_channel` configuration, setting it to this compiler's release channel.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a directive could not be emitted.
+    pub fn try_declare_channel(&self) -> Result<(), Error> {
+        let channels = Channel::ALL.map(Channel::as_str);
+
+        Cfg::try_new(CHANNEL_KEY)?.try_assigned_one_of(&channels)?.try_set(Some(self.channel.as_str()))
+    }
+
+    /// Declares the `rustc_date` configuration, setting it to this compiler's commit date if it is known.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a directive could not be emitted.
+    pub fn declare_date(&self) {
+        self.try_declare_date().unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Declares the `rustc_date` configuration, setting it to this compiler's commit date if it is known.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a directive could not be emitted.
+    pub fn try_declare_date(&self) -> Result<(), Error> {
+        let cfg = Cfg::try_new(DATE_KEY)?.try_assigned_any()?;
+
+        self.commit_date().map_or(Ok(()), |date| cfg.try_set(Some(date)))
+    }
+}
+
+/// Returns a command that runs the compiler used by the current build, through its wrapper if one is set.
+pub(crate) fn command() -> Command {
+    let variable = |key| crate::env::var_os(key).filter(|value| !value.is_empty());
+    let rustc = variable("RUSTC").unwrap_or_else(|| OsString::from("rustc"));
+
+    match variable("RUSTC_WORKSPACE_WRAPPER").or_else(|| variable("RUSTC_WRAPPER")) {
+        Some(wrapper) => {
+            let mut command = Command::new(wrapper);
+
+            command.arg(rustc);
+            command
+        }
+        None => Command::new(rustc),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rustc;
+    use crate::testing::Harness;
+    use crate::version::{Channel, Version};
+    use crate::{Directive, Error};
+
+    /// Returns the output of `rustc -vV` for the given release and commit hash.
+    fn output(release: &str, commit_hash: &str) -> String {
+        format!(
+            "rustc {release} ({commit_hash} 2024-08-06)\nbinary: rustc\ncommit-hash: {commit_hash}\ncommit-date: \
+             2024-08-06\nhost: x86_64-unknown-linux-gnu\nrelease: {release}\nLLVM version: 18.1.7\n"
+        )
+    }
+
+    /// Parses the output of `rustc -vV` for the given release and commit hash.
+    fn parse(release: &str, commit_hash: &str) -> Result<Rustc, Error> {
+        Rustc::parse(&output(release, commit_hash))
+    }
+
+    #[test]
+    fn releases_are_parsed_with_their_channel() -> Result<(), Error> {
+        let stable = parse("1.80.1", "3f5fd8dd41153bc5fdca9427e9e05be2c767ba23")?;
+
+        assert_eq!(stable.version(), Version::new(1, 80, 1));
+        assert_eq!(stable.channel(), Channel::Stable);
+        assert_eq!(stable.commit_hash(), Some("3f5fd8dd41153bc5fdca9427e9e05be2c767ba23"));
+        assert_eq!(stable.commit_date(), Some("2024-08-06"));
+        assert_eq!(stable.host(), Some("x86_64-unknown-linux-gnu"));
+
+        for (release, version, channel) in [
+            ("1.81.0-beta.3", Version::new(1, 81, 0), Channel::Beta),
+            ("1.82.0-nightly", Version::new(1, 82, 0), Channel::Nightly),
+            ("1.83.0-dev", Version::new(1, 83, 0), Channel::Dev),
+        ] {
+            let rustc = parse(release, "unknown")?;
+
+            assert_eq!(rustc.version(), version, "{release}");
+            assert_eq!(rustc.channel(), channel, "{release}");
+            assert_eq!(rustc.commit_hash(), None, "{release}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_releases_are_rejected() {
+        assert!(matches!(Rustc::parse("rustc 1.80.1\nbinary: rustc\n"), Err(Error::InvalidVersion { .. })));
+        assert!(matches!(parse("1.80.x", "unknown"), Err(Error::InvalidVersion { .. })));
+        assert!(matches!(parse("1.80.1-alpha", "unknown"), Err(Error::InvalidVersion { .. })));
+    }
+
+    #[test]
+    fn satisfied_versions_are_set() -> Result<(), Error> {
+        let rustc = parse("1.80.1", "unknown")?;
+        let (result, directives) = Harness::new().run(|| rustc.try_declare_versions(78..=81));
+        let set: Vec<_> = directives
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Cfg { key, value: None } => Some(&**key),
+                _ => None,
+            })
+            .collect();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(set, ["rustc_1_78", "rustc_1_79", "rustc_1_80"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Defines traits that map Rust types onto configurations.
+//!
+//! These are usually implemented using the `Cfg` derive macro, which is available through the `derive` feature.
+
+use std::str::FromStr;
+
+use crate::{Cfg, CheckedCfg, Error};
+
+/// An enum whose variants are the values of a single configuration.
+pub trait CfgEnum: FromStr + Sized + 'static {
+    /// The configuration key.
+    const KEY: &'static str;
+
+    /// Every value that the configuration may be assigned, in declaration order.
+    const VALUES: &'static [&'static str];
+
+    /// Returns the configuration value that represents this variant.
+    fn as_str(&self) -> &'static str;
+
+    /// Declares the configuration and registers it.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the configuration could not be declared.
+    #[must_use = "this value does nothing unless used"]
+    fn declare() -> impl CheckedCfg<'static> {
+        Self::try_declare().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Declares the configuration and registers it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration could not be declared.
+    fn try_declare() -> Result<impl CheckedCfg<'static>, Error> {
+        <Self as CfgField>::try_declare(Self::KEY)
+    }
+}
+
+/// A struct whose fields each represent a separate configuration.
+pub trait CfgSchema: Sized {
+    /// Declares and registers every configuration without setting them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a configuration could not be declared.
+    fn try_declare() -> Result<(), Error>;
+
+    /// Declares every configuration, setting each of them from the value of its field.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a configuration could not be declared or set.
+    fn try_set(&self) -> Result<(), Error>;
+
+    /// Declares every configuration, setting each of them from its environment variable.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a configuration could not be declared, or an environment variable could
+    /// not be read or contained a value that is not assignable to its configuration.
+    fn try_from_env() -> Result<Self, Error>;
+
+    /// Declares every configuration, setting each of them from its environment variable.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a configuration could not be declared, or an environment variable could not be read
+    /// or contained a value that is not assignable to its configuration.
+    #[must_use]
+    fn from_env() -> Self {
+        Self::try_from_env().unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+/// A type that may be used as a field of a [`CfgSchema`].
+///
+/// This is implemented for every [`CfgEnum`], [`Option`]s of them, [`bool`] for flags, and [`String`] for
+/// configurations that may be assigned any value.
+pub trait CfgField: Sized {
+    /// Declares a configuration with the given key that accepts this type and registers it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration could not be declared.
+    fn try_declare(key: &'static str) -> Result<impl CheckedCfg<'static>, Error>;
+
+    /// Parses the given configuration value.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the value is not assignable to the configuration.
+    fn try_parse(key: &str, value: Option<&str>) -> Result<Self, Error>;
+
+    /// Returns the value that should be assigned to the configuration, or `None` if it should not be set.
+    fn assignment(&self) -> Option<Option<&str>>;
+
+    /// Declares a configuration with the given key, setting it from the value of this field.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration could not be declared or set.
+    fn try_set(&self, key: &'static str) -> Result<(), Error> {
+        let cfg = Self::try_declare(key)?;
+
+        self.assignment().map_or(Ok(()), |value| cfg.try_set(value))
+    }
+
+    /// Declares a configuration with the given key, setting it from the given environment variable.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration could not be declared, or the environment variable could
+    /// not be read or contained a value that is not assignable to the configuration.
+    fn try_from_env(key: &'static str, variable_key: &str) -> Result<Self, Error> {
+        let cfg = Self::try_declare(key)?;
+        let value = crate::env::read(variable_key)?;
+        let value = Self::try_parse(key, value.as_deref()).map_err(|error| match value {
+            Some(_) => error.in_variable(variable_key),
+            None => error,
+        })?;
+
+        if let Some(assignment) = value.assignment() {
+            cfg.try_set(assignment)?;
+        }
+
+        Ok(value)
+    }
+}
+
+/// Returns an error stating that the given value is not assignable to the given configuration.
+fn unassignable(key: &str, value: Option<&str>, allowed: &[&str]) -> Error {
+    Error::Unassignable {
+        key: key.into(),
+        value: value.map(Into::into),
+        allowed: allowed.iter().map(|&v| v.into()).collect(),
+    }
+}
+
+impl<T: CfgEnum> CfgField for T {
+    fn try_declare(key: &'static str) -> Result<impl CheckedCfg<'static>, Error> {
+        Cfg::try_new(key)?.try_assigned_one_of(T::VALUES)
+    }
+
+    fn try_parse(key: &str, value: Option<&str>) -> Result<Self, Error> {
+        value.and_then(|v| v.parse().ok()).ok_or_else(|| self::unassignable(key, value, T::VALUES))
+    }
+
+    fn assignment(&self) -> Option<Option<&str>> {
+        Some(Some(self.as_str()))
+    }
+}
+
+impl<T: CfgEnum> CfgField for Option<T> {
+    fn try_declare(key: &'static str) -> Result<impl CheckedCfg<'static>, Error> {
+        Cfg::try_new(key)?.try_assigned_none_or_one_of(T::VALUES)
+    }
+
+    fn try_parse(key: &str, value: Option<&str>) -> Result<Self, Error> {
+        value.map(|v| T::try_parse(key, Some(v))).transpose()
+    }
+
+    fn assignment(&self) -> Option<Option<&str>> {
+        self.as_ref().map(|v| Some(v.as_str()))
+    }
+}
+
+impl CfgField for bool {
+    fn try_declare(key: &'static str) -> Result<impl CheckedCfg<'static>, Error> {
+        Cfg::try_new(key)?.try_flag()
+    }
+
+    fn try_parse(key: &str, value: Option<&str>) -> Result<Self, Error> {
+        value
+            .map_or(Some(false), crate::env::parse_flag)
+            .ok_or_else(|| self::unassignable(key, value, &crate::env::FLAG_VALUES))
+    }
+
+    fn assignment(&self) -> Option<Option<&str>> {
+        self.then_some(None)
+    }
+}
+
+impl CfgField for String {
+    fn try_declare(key: &'static str) -> Result<impl CheckedCfg<'static>, Error> {
+        Cfg::try_new(key)?.try_assigned_any()
+    }
+
+    fn try_parse(key: &str, value: Option<&str>) -> Result<Self, Error> {
+        value.map(Into::into).ok_or_else(|| self::unassignable(key, value, &[]))
+    }
+
+    fn assignment(&self) -> Option<Option<&str>> {
+        Some(Some(self))
+    }
+}
@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements declarative selection of a configuration's value based on predicates.
+
+use std::fmt::{Debug, Display};
+
+use crate::{CheckedCfg, Error, Predicate, Target};
+
+/// A list of rules that select the value of a configuration, created by [`CheckedCfg::select`].
+///
+/// Rules are checked in the order that they were added, and the value of the first rule whose predicate holds is
+/// assigned. If no rule holds, the fallback value is assigned, if any.
+///
+/// ```no_run
+/// use fig::{Cfg, CheckedCfg};
+///
+/// let backend = Cfg::new("backend").assigned_one_of(&["epoll", "iocp", "poll"]);
+/// let select = backend.select().when(r#"target_os = "linux""#, "epoll").when("windows", "iocp").fallback("poll");
+///
+/// // Prints `backend = "epoll" if target_os = "linux"`, and so on.
+/// println!("{select}");
+///
+/// select.apply();
+/// ```
+#[must_use = "this value does nothing unless applied"]
+pub struct Select<'c, C: ?Sized> {
+    /// The configuration being selected.
+    cfg: &'c C,
+    /// The rules, in the order that they are checked.
+    rules: Vec<(Predicate, Option<Box<str>>)>,
+    /// The value assigned if no rule holds.
+    fallback: Fallback,
+    /// The first error encountered while adding rules.
+    error: Option<Error>,
+}
+
+/// The value assigned by a [`Select`] if no rule holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Fallback {
+    /// The configuration is not set.
+    Unset,
+    /// The configuration is set to the given value.
+    Set(Option<Box<str>>),
+}
+
+impl<'c, C: ?Sized> Select<'c, C> {
+    /// Creates a new [`Select`] for the given configuration without any rules.
+    pub(crate) const fn new(cfg: &'c C) -> Self {
+        Self { cfg, rules: Vec::new(), fallback: Fallback::Unset, error: None }
+    }
+
+    /// Adds a rule that assigns the given value if the given predicate holds.
+    ///
+    /// If the predicate is invalid, the error is reported once the selection is applied.
+    pub fn when<'v>(mut self, predicate: &str, value: impl Into<Option<&'v str>>) -> Self {
+        match Predicate::parse(predicate) {
+            Ok(predicate) => self.rules.push((predicate, value.into().map(Into::into))),
+            Err(error) => self.error = self.error.or(Some(error)),
+        }
+
+        self
+    }
+
+    /// Adds a rule that assigns the given value if the given predicate holds.
+    pub fn when_predicate<'v>(mut self, predicate: Predicate, value: impl Into<Option<&'v str>>) -> Self {
+        self.rules.push((predicate, value.into().map(Into::into)));
+
+        self
+    }
+
+    /// Sets the value that is assigned if no rule holds.
+    pub fn fallback<'v>(mut self, value: impl Into<Option<&'v str>>) -> Self {
+        self.fallback = Fallback::Set(value.into().map(Into::into));
+
+        self
+    }
+
+    /// Returns an iterator over every rule and the value that it assigns, in the order that they are checked.
+    pub fn rules(&self) -> impl Iterator<Item = (&Predicate, Option<&str>)> {
+        self.rules.iter().map(|(predicate, value)| (predicate, value.as_deref()))
+    }
+
+    /// Returns the value that is assigned if no rule holds, or `None` if there is no fallback.
+    #[must_use]
+    pub fn fallback_value(&self) -> Option<Option<&str>> {
+        match &self.fallback {
+            Fallback::Unset => None,
+            Fallback::Set(value) => Some(value.as_deref()),
+        }
+    }
+
+    /// Returns the value selected by the given target, or `None` if no rule holds and there is no fallback.
+    ///
+    /// Rules whose predicates were invalid are not included.
+    #[must_use]
+    pub fn evaluate(&self, target: &Target) -> Option<Option<&str>> {
+        let selected = self.rules().find(|(predicate, _)| predicate.evaluate(target)).map(|(_, value)| value);
+
+        selected.or_else(|| self.fallback_value())
+    }
+}
+
+impl<'s, C: CheckedCfg<'s> + ?Sized> Select<'_, C> {
+    /// Assigns the value of the first rule that holds for the current target, or the fallback value.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a rule's predicate was invalid, the target could not be detected, or the selected
+    /// value could not be assigned.
+    pub fn apply(self) {
+        self.try_apply().unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Assigns the value of the first rule that holds for the current target, or the fallback value.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a rule's predicate was invalid, the target could not be detected, or the
+    /// selected value could not be assigned.
+    pub fn try_apply(mut self) -> Result<(), Error> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        match self.evaluate(&*Target::current()?) {
+            Some(value) => self.cfg.try_set(value),
+            None => Ok(()),
+        }
+    }
+
+    /// Assigns the value of the first rule that holds for the current target, or the given value.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a rule's predicate was invalid, the target could not be detected, or the selected
+    /// value could not be assigned.
+    pub fn otherwise<'v>(self, value: impl Into<Option<&'v str>>) {
+        self.fallback(value).apply();
+    }
+
+    /// Assigns the value of the first rule that holds for the current target, or the given value.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a rule's predicate was invalid, the target could not be detected, or the
+    /// selected value could not be assigned.
+    pub fn try_otherwise<'v>(self, value: impl Into<Option<&'v str>>) -> Result<(), Error> {
+        self.fallback(value).try_apply()
+    }
+}
+
+impl<'s, C: CheckedCfg<'s> + ?Sized> Display for Select<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// Writes the assignment of the given value.
+        fn assignment(f: &mut std::fmt::Formatter<'_>, key: &str, value: Option<&str>) -> std::fmt::Result {
+            match value {
+                Some(value) => write!(f, "{key} = {}", crate::directive::to_str_literal(value)),
+                None => f.write_str(key),
+            }
+        }
+
+        for (index, (predicate, value)) in self.rules().enumerate() {
+            if index != 0 {
+                f.write_str("\n")?;
+            }
+
+            assignment(f, self.cfg.key(), value)?;
+
+            write!(f, " if {predicate}")?;
+        }
+
+        if let Some(value) = self.fallback_value() {
+            if !self.rules.is_empty() {
+                f.write_str("\n")?;
+            }
+
+            assignment(f, self.cfg.key(), value)?;
+
+            f.write_str(" otherwise")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: ?Sized> Debug for Select<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Select")
+            .field("rules", &self.rules)
+            .field("fallback", &self.fallback)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
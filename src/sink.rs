@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements the sinks that emitted directives are routed through.
+//!
+//! Every directive emitted by this crate is sent to the current thread's sink, which writes to standard output unless
+//! it has been replaced using [`set_sink`] or [`with_sink`].
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::rc::{Rc, Weak};
+
+use crate::{Directive, Error};
+
+thread_local! {
+    /// The sink used by the current thread, or `None` if directives should be written to standard output.
+    static SINK: RefCell<Option<Box<dyn Sink>>> = const { RefCell::new(None) };
+}
+
+/// The values assigned to each configuration.
+type Assignments = BTreeMap<Box<str>, BTreeSet<Option<Box<str>>>>;
+
+thread_local! {
+    /// Every configuration that has been set on the current thread.
+    static ASSIGNMENTS: RefCell<Assignments> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Emits the given directive to the current thread's sink.
+pub(crate) fn emit(directive: Directive) -> Result<(), Error> {
+    let assignment = match &directive {
+        Directive::Cfg { key, value } => Some((key.clone(), value.clone())),
+        _ => None,
+    };
+
+    SINK.with_borrow_mut(|sink| match sink {
+        Some(sink) => sink.emit(directive),
+        None => Stdout.emit(directive),
+    })?;
+
+    if let Some((key, value)) = assignment {
+        ASSIGNMENTS.with_borrow_mut(|assignments| assignments.entry(key).or_default().insert(value));
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the given configuration has been set to the given value on the current thread.
+pub(crate) fn is_assigned(key: &str, value: Option<&str>) -> bool {
+    ASSIGNMENTS
+        .with_borrow(|assignments| assignments.get(key).is_some_and(|values| values.contains(&value.map(Into::into))))
+}
+
+/// Runs the given function without any configurations being considered set, restoring them afterwards.
+pub(crate) fn with_empty_assignments<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    /// Restores the previous assignments when dropped.
+    struct Guard(Assignments);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            ASSIGNMENTS.set(std::mem::take(&mut self.0));
+        }
+    }
+
+    let _guard = Guard(ASSIGNMENTS.take());
+
+    f()
+}
+
+/// Replaces the current thread's sink, returning the previous sink.
+pub fn set_sink<S: Sink + 'static>(sink: S) -> Box<dyn Sink> {
+    SINK.replace(Some(Box::new(sink))).unwrap_or_else(|| Box::new(Stdout))
+}
+
+/// Routes every directive emitted by the given function to the given sink, returning the sink afterwards.
+///
+/// The previous sink is restored once the function returns, even if it panics. If the function replaces the sink using
+/// [`set_sink`], the replaced sink returns an error from [`Sink::emit`] once this function returns.
+pub fn with_sink<S, F, T>(sink: S, f: F) -> (T, S)
+where
+    S: Sink + 'static,
+    F: FnOnce() -> T,
+{
+    /// Restores the previous sink when dropped.
+    struct Guard(Option<Box<dyn Sink>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            SINK.set(self.0.take());
+        }
+    }
+
+    /// Forwards directives to a sink that is owned by the caller, failing once the caller has taken it back.
+    struct Shared<S>(Weak<RefCell<S>>);
+
+    impl<S: Sink> Sink for Shared<S> {
+        fn emit(&mut self, directive: Directive) -> Result<(), Error> {
+            let Some(sink) = self.0.upgrade() else {
+                let source = std::io::Error::other("the sink was returned by `with_sink` and is no longer active");
+
+                return Err(Error::Output { source });
+            };
+
+            sink.borrow_mut().emit(directive)
+        }
+    }
+
+    let shared = Rc::new(RefCell::new(sink));
+    let guard = Guard(SINK.replace(Some(Box::new(Shared(Rc::downgrade(&shared))))));
+    let output = f();
+
+    drop(guard);
+
+    // Only weak references are handed out, so this is the only strong reference.
+    let Ok(sink) = Rc::try_unwrap(shared) else { unreachable!("the shared sink should not have been upgraded") };
+
+    (output, sink.into_inner())
+}
+
+/// A destination for emitted directives.
+pub trait Sink {
+    /// Emits the given directive.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directive could not be emitted.
+    fn emit(&mut self, directive: Directive) -> Result<(), Error>;
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn emit(&mut self, directive: Directive) -> Result<(), Error> {
+        (**self).emit(directive)
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn emit(&mut self, directive: Directive) -> Result<(), Error> {
+        (**self).emit(directive)
+    }
+}
+
+/// A sink that writes directives to standard output, as expected by Cargo.
+///
+/// Directives are written in the same way as [`Writer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stdout;
+
+impl Sink for Stdout {
+    fn emit(&mut self, directive: Directive) -> Result<(), Error> {
+        Writer::new(std::io::stdout().lock()).emit(directive)
+    }
+}
+
+/// A sink that stores directives in memory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Buffer(Vec<Directive>);
+
+impl Buffer {
+    /// Creates a new empty [`Buffer`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Returns the directives stored within this buffer, in the order that they were emitted.
+    #[must_use]
+    pub fn directives(&self) -> &[Directive] {
+        &self.0
+    }
+
+    /// Returns the directives stored within this buffer, in the order that they were emitted.
+    #[must_use]
+    pub fn into_directives(self) -> Vec<Directive> {
+        self.0
+    }
+}
+
+impl Sink for Buffer {
+    fn emit(&mut self, directive: Directive) -> Result<(), Error> {
+        self.0.push(directive);
+
+        Ok(())
+    }
+}
+
+/// A sink that writes directives to an arbitrary writer, one per line.
+///
+/// Directives that cannot be written on a single line are rejected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Writer<W> {
+    /// The underlying writer.
+    inner: W,
+}
+
+impl<W> Writer<W> {
+    /// Creates a new [`Writer`] that writes directives to the given writer.
+    pub const fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub const fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub const fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Sink for Writer<W> {
+    fn emit(&mut self, directive: Directive) -> Result<(), Error> {
+        directive.validate()?;
+
+        writeln!(self.inner, "{directive}").map_err(|source| Error::Output { source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Buffer, Sink};
+    use crate::{Directive, Error};
+
+    /// Returns a directive to emit.
+    fn directive() -> Directive {
+        Directive::Cfg { key: "key".into(), value: None }
+    }
+
+    #[test]
+    fn with_sink_returns_the_sink() {
+        let (result, buffer) = super::with_sink(Buffer::new(), || super::emit(directive()));
+
+        assert!(result.is_ok());
+        assert_eq!(buffer.directives(), [directive()]);
+    }
+
+    #[test]
+    fn replaced_sinks_may_escape_with_sink() {
+        let (mut escaped, buffer) = super::with_sink(Buffer::new(), || super::set_sink(Buffer::new()));
+
+        assert!(buffer.directives().is_empty());
+        assert!(matches!(escaped.emit(directive()), Err(Error::Output { .. })));
+    }
+}
@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements typed access to the `cfg` predicates of the target being built for.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+use crate::{Directive, Error};
+
+/// The prefix of the environment variables that Cargo uses to describe the target.
+const PREFIX: &str = "CARGO_CFG_";
+
+/// The predicates that rustc always sets to a value, which may be empty, such as `target_abi = ""`.
+const VALUED: &[&str] = &[
+    "fmt_debug",
+    "panic",
+    "relocation_model",
+    "sanitize",
+    "target_abi",
+    "target_arch",
+    "target_endian",
+    "target_env",
+    "target_family",
+    "target_feature",
+    "target_has_atomic",
+    "target_has_atomic_equal_alignment",
+    "target_has_atomic_load_store",
+    "target_object_format",
+    "target_os",
+    "target_pointer_width",
+    "target_vendor",
+];
+
+thread_local! {
+    /// The target detected on the current thread, or `None` if it has not been detected yet.
+    static CURRENT: RefCell<Option<Rc<Target>>> = const { RefCell::new(None) };
+}
+
+/// The byte order of a target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Endian {
+    /// The least significant byte is stored first.
+    Little,
+    /// The most significant byte is stored first.
+    Big,
+}
+
+/// The `cfg` predicates of the target being built for, as reported by Cargo through `CARGO_CFG_*` variables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Target {
+    /// The values of every predicate, keyed by name. Predicates without values map to an empty set.
+    cfgs: BTreeMap<Box<str>, BTreeSet<Box<str>>>,
+}
+
+impl Target {
+    /// Reads the predicates of the current target, registering each variable as a dependency of the build script.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if a variable does not contain valid unicode, or a directive could not be emitted.
+    #[must_use]
+    pub fn detect() -> Self {
+        Self::try_detect().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Reads the predicates of the current target, registering each variable as a dependency of the build script.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a variable does not contain valid unicode, or a directive could not be
+    /// emitted.
+    pub fn try_detect() -> Result<Self, Error> {
+        let mut cfgs = BTreeMap::new();
+
+        for (key, value) in crate::env::vars_with_prefix(PREFIX) {
+            let key = key.to_string_lossy();
+            let value = value.into_string().map_err(|_| Error::NonUnicodeVariable { variable: key.as_ref().into() })?;
+
+            crate::sink::emit(Directive::RerunIfEnvChanged { variable: key.as_ref().into() })?;
+
+            let name = key[PREFIX.len()..].to_lowercase().into_boxed_str();
+            let values = if value.is_empty() && VALUED.contains(&&*name) {
+                BTreeSet::from([Box::from("")])
+            } else {
+                value.split(',').filter(|v| !v.is_empty()).map(Into::into).collect()
+            };
+
+            cfgs.insert(name, values);
+        }
+
+        Ok(Self { cfgs })
+    }
+
+    /// Returns the target detected on the current thread, detecting it if this is the first call.
+    ///
+    /// Each variable is only registered as a dependency once, rather than every time that a predicate is evaluated.
+    pub(crate) fn current() -> Result<Rc<Self>, Error> {
+        if let Some(target) = CURRENT.with_borrow(Clone::clone) {
+            return Ok(target);
+        }
+
+        let target = Rc::new(Self::try_detect()?);
+
+        CURRENT.set(Some(Rc::clone(&target)));
+
+        Ok(target)
+    }
+
+    /// Forgets the target detected on the current thread, so that it is detected again by the next call to
+    /// [`Target::current`].
+    pub(crate) fn forget_current() {
+        CURRENT.set(None);
+    }
+
+    /// Returns the values of the given predicate, or `None` if it is not set.
+    ///
+    /// Predicates that are set without a value, such as `unix`, return an empty set. Predicates that rustc always sets
+    /// to a value, such as `target_abi`, contain the empty string if their value is empty.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&BTreeSet<Box<str>>> {
+        self.cfgs.get(key)
+    }
+
+    /// Returns `true` if the given predicate is set without a value, such as `unix`.
+    #[must_use]
+    pub fn is_flag(&self, key: &str) -> bool {
+        self.get(key).is_some_and(BTreeSet::is_empty)
+    }
+
+    /// Returns `true` if the given predicate is set.
+    #[must_use]
+    pub fn has(&self, key: &str) -> bool {
+        self.cfgs.contains_key(key)
+    }
+
+    /// Returns `true` if the given predicate is set to the given value.
+    #[must_use]
+    pub fn has_value(&self, key: &str, value: &str) -> bool {
+        self.get(key).is_some_and(|values| values.contains(value))
+    }
+
+    /// Returns an iterator over every predicate and its values.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BTreeSet<Box<str>>)> {
+        self.cfgs.iter().map(|(key, values)| (&**key, values))
+    }
+
+    /// Returns the single value of the given predicate, if it is set.
+    fn single(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(|values| values.first()).map(|value| &**value)
+    }
+
+    /// Returns the target architecture, such as `x86_64`.
+    #[must_use]
+    pub fn arch(&self) -> Option<&str> {
+        self.single("target_arch")
+    }
+
+    /// Returns the target operating system, such as `linux`.
+    #[must_use]
+    pub fn os(&self) -> Option<&str> {
+        self.single("target_os")
+    }
+
+    /// Returns the target environment, such as `gnu`.
+    #[must_use]
+    pub fn env(&self) -> Option<&str> {
+        self.single("target_env")
+    }
+
+    /// Returns the target ABI, such as `eabihf`.
+    #[must_use]
+    pub fn abi(&self) -> Option<&str> {
+        self.single("target_abi")
+    }
+
+    /// Returns the target vendor, such as `unknown`.
+    #[must_use]
+    pub fn vendor(&self) -> Option<&str> {
+        self.single("target_vendor")
+    }
+
+    /// Returns the panic strategy, such as `unwind`.
+    #[must_use]
+    pub fn panic(&self) -> Option<&str> {
+        self.single("panic")
+    }
+
+    /// Returns the target's byte order.
+    #[must_use]
+    pub fn endian(&self) -> Option<Endian> {
+        match self.single("target_endian")? {
+            "little" => Some(Endian::Little),
+            "big" => Some(Endian::Big),
+            _ => None,
+        }
+    }
+
+    /// Returns the width of a pointer on the target, in bits.
+    #[must_use]
+    pub fn pointer_width(&self) -> Option<u32> {
+        self.single("target_pointer_width")?.parse().ok()
+    }
+
+    /// Returns the target families, such as `unix`.
+    pub fn families(&self) -> impl Iterator<Item = &str> {
+        self.values("target_family")
+    }
+
+    /// Returns the enabled target features, such as `sse2`.
+    pub fn features(&self) -> impl Iterator<Item = &str> {
+        self.values("target_feature")
+    }
+
+    /// Returns `true` if the given target feature is enabled.
+    #[must_use]
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.has_value("target_feature", feature)
+    }
+
+    /// Returns the widths of the atomic types supported by the target, such as `64` or `ptr`.
+    pub fn atomics(&self) -> impl Iterator<Item = &str> {
+        self.values("target_has_atomic")
+    }
+
+    /// Returns `true` if the target is a Unix platform.
+    #[must_use]
+    pub fn is_unix(&self) -> bool {
+        self.has("unix")
+    }
+
+    /// Returns `true` if the target is a Windows platform.
+    #[must_use]
+    pub fn is_windows(&self) -> bool {
+        self.has("windows")
+    }
+
+    /// Returns `true` if debug assertions are enabled.
+    #[must_use]
+    pub fn debug_assertions(&self) -> bool {
+        self.has("debug_assertions")
+    }
+
+    /// Returns the values of the given predicate, which is empty if it is not set.
+    fn values(&self, key: &str) -> impl Iterator<Item = &str> {
+        self.get(key).into_iter().flatten().map(|value| &**value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Target;
+    use crate::testing::Harness;
+
+    /// Detects the target described by the given variables.
+    fn detect(variables: &[(&str, &str)]) -> Target {
+        let (target, _) = Harness::new().envs(variables.iter().copied()).run(Target::try_detect);
+
+        target.unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    #[test]
+    fn empty_values_are_kept_for_valued_predicates() {
+        let target = detect(&[("CARGO_CFG_TARGET_ABI", ""), ("CARGO_CFG_TARGET_ENV", ""), ("CARGO_CFG_UNIX", "")]);
+
+        assert_eq!(target.abi(), Some(""));
+        assert_eq!(target.env(), Some(""));
+        assert!(target.has_value("target_abi", ""));
+        assert!(!target.is_flag("target_abi"));
+        assert!(target.is_flag("unix"));
+        assert!(target.is_unix());
+    }
+
+    #[test]
+    fn values_are_split_on_commas() {
+        let target = detect(&[
+            ("CARGO_CFG_TARGET_FAMILY", "unix"),
+            ("CARGO_CFG_TARGET_FEATURE", "fxsr,sse,sse2"),
+            ("CARGO_CFG_TARGET_HAS_ATOMIC", "16,32,64,8,ptr"),
+            ("CARGO_CFG_TARGET_POINTER_WIDTH", "64"),
+            ("CARGO_CFG_TARGET_ENDIAN", "little"),
+        ]);
+
+        assert_eq!(target.families().collect::<Vec<_>>(), ["unix"]);
+        assert!(target.has_feature("sse2"));
+        assert!(!target.has_feature("avx"));
+        assert_eq!(target.atomics().count(), 5);
+        assert_eq!(target.pointer_width(), Some(64));
+        assert_eq!(target.endian(), Some(super::Endian::Little));
+    }
+}
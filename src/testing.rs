@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Provides utilities for testing build script logic without touching the process environment or standard output.
+//!
+//! ```
+//! use fig::testing::Harness;
+//! use fig::{Cfg, CheckedCfg, Directive};
+//!
+//! let ((), directives) = Harness::new().env("BACKEND", "epoll").run(|| {
+//!     Cfg::new("backend").assigned_one_of(&["epoll", "kqueue"]).set_from_env("BACKEND");
+//! });
+//!
+//! assert!(directives.contains(&Directive::Cfg { key: "backend".into(), value: Some("epoll".into()) }));
+//! ```
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use crate::Directive;
+use crate::sink::Buffer;
+
+/// Runs build script logic against a mocked environment, capturing every emitted directive.
+#[must_use = "this value does nothing unless ran"]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Harness {
+    /// The environment variables visible to the build script logic.
+    variables: BTreeMap<OsString, OsString>,
+}
+
+impl Harness {
+    /// Creates a new [`Harness`] with an empty environment.
+    pub const fn new() -> Self {
+        Self { variables: BTreeMap::new() }
+    }
+
+    /// Sets the given environment variable within the mocked environment.
+    pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.variables.insert(key.into(), value.into());
+
+        self
+    }
+
+    /// Sets every given environment variable within the mocked environment.
+    pub fn envs<I, K, V>(mut self, variables: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<OsString>,
+        V: Into<OsString>,
+    {
+        self.variables.extend(variables.into_iter().map(|(k, v)| (k.into(), v.into())));
+
+        self
+    }
+
+    /// Runs the given function, returning its output and every directive that it emitted, in order.
+    ///
+    /// Only the variables set on this harness are visible to the function, no configurations are considered to have
+    /// been set beforehand, and nothing is written to standard output.
+    pub fn run<F, T>(self, f: F) -> (T, Vec<Directive>)
+    where
+        F: FnOnce() -> T,
+    {
+        let (output, buffer) = crate::env::with_vars(self.variables, || {
+            crate::sink::with_empty_assignments(|| crate::sink::with_sink(Buffer::new(), f))
+        });
+
+        (output, buffer.into_directives())
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Implements parsing and comparison of Rust toolchain versions.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// A toolchain version, such as `1.80.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// The major version.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch version.
+    pub patch: u32,
+}
+
+impl Version {
+    /// Creates a new [`Version`].
+    #[must_use]
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Parses a version from the given string, such as `1.80.0`, `1.80`, or `1`.
+    ///
+    /// A missing minor or patch version is treated as zero.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the string is not a valid version.
+    pub fn parse(version: &str) -> Result<Self, Error> {
+        let error = || Error::InvalidVersion { version: version.into() };
+        let mut parts = version.split('.').map(|part| part.parse::<u32>().map_err(|_| error()));
+
+        let major = parts.next().ok_or_else(error)??;
+        let minor = parts.next().transpose()?.unwrap_or(0);
+        let patch = parts.next().transpose()?.unwrap_or(0);
+
+        if parts.next().is_some() {
+            return Err(error());
+        }
+
+        Ok(Self { major, minor, patch })
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A toolchain release channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Channel {
+    /// The stable channel.
+    Stable,
+    /// The beta channel.
+    Beta,
+    /// The nightly channel.
+    Nightly,
+    /// A toolchain built from source.
+    Dev,
+}
+
+impl Channel {
+    /// Every channel, in order of stability.
+    pub const ALL: [Self; 4] = [Self::Stable, Self::Beta, Self::Nightly, Self::Dev];
+
+    /// Returns the channel of a release with the given pre-release suffix, such as `nightly` or `beta.2`.
+    ///
+    /// An empty suffix is a stable release.
+    #[must_use]
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix.split('.').next()? {
+            "" => Some(Self::Stable),
+            "beta" => Some(Self::Beta),
+            "nightly" => Some(Self::Nightly),
+            "dev" => Some(Self::Dev),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of this channel, such as `nightly`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+            Self::Nightly => "nightly",
+            Self::Dev => "dev",
+        }
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
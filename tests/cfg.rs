@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Tests the ways that configurations are set, using a mocked environment.
+
+use fig::normalize::Normalize;
+use fig::testing::Harness;
+use fig::{Cfg, CheckedCfg, Directive, Error};
+
+/// Returns a directive that sets the given key.
+fn cfg(key: &str, value: Option<&str>) -> Directive {
+    Directive::Cfg { key: key.into(), value: value.map(Into::into) }
+}
+
+#[test]
+fn features_select_a_value_and_conflict() {
+    let features = [("epoll", Some("epoll")), ("io-uring", Some("io_uring"))];
+    let set = |harness: Harness| {
+        harness.run(|| {
+            Cfg::try_new("backend")?.try_assigned_one_of(&["epoll", "io_uring"])?.try_set_from_features(&features)
+        })
+    };
+
+    let (result, directives) = set(Harness::new().env("CARGO_FEATURE_IO_URING", "1"));
+
+    assert!(result.is_ok(), "{result:?}");
+    assert!(directives.contains(&cfg("backend", Some("io_uring"))));
+
+    let (result, directives) = set(Harness::new());
+
+    assert!(result.is_ok(), "{result:?}");
+    assert!(!directives.iter().any(|directive| matches!(directive, Directive::Cfg { .. })));
+
+    let (result, _) = set(Harness::new().env("CARGO_FEATURE_EPOLL", "1").env("CARGO_FEATURE_IO_URING", "1"));
+
+    assert!(matches!(
+        result,
+        Err(Error::ConflictingFeatures { key, features }) if &*key == "backend" && features.len() == 2
+    ));
+}
+
+#[test]
+fn aliases_may_refer_to_earlier_aliases() {
+    let harness = Harness::new().env("CARGO_CFG_TARGET_ARCH", "wasm32").env("CARGO_CFG_TARGET_OS", "unknown");
+    let (result, directives) = harness.run(|| {
+        let browser =
+            Cfg::try_new("wasm_browser")?.try_alias(r#"all(target_arch = "wasm32", not(target_os = "wasi"))"#)?;
+        let web = Cfg::try_new("web")?.try_alias("any(wasm_browser, target_os = \"emscripten\")")?;
+        let native = Cfg::try_new("native")?.try_alias("not(web)")?;
+
+        Ok::<_, Error>((browser, web, native))
+    });
+
+    assert_eq!(result.ok(), Some((true, true, false)));
+    assert!(directives.contains(&cfg("wasm_browser", None)));
+    assert!(directives.contains(&cfg("web", None)));
+    assert!(!directives.contains(&cfg("native", None)));
+}
+
+#[test]
+fn selections_pick_their_first_match_or_fallback() {
+    let select = |os: &str| {
+        Harness::new().env("CARGO_CFG_TARGET_OS", os).env("CARGO_CFG_UNIX", "").run(|| {
+            let backend = Cfg::try_new("backend")?.try_assigned_one_of(&["epoll", "kqueue", "poll"])?;
+
+            backend
+                .select()
+                .when(r#"target_os = "linux""#, "epoll")
+                .when("unix", "kqueue")
+                .when(r#"target_os = "linux""#, "poll")
+                .try_otherwise("poll")
+        })
+    };
+
+    let (result, directives) = select("linux");
+
+    assert!(result.is_ok(), "{result:?}");
+    assert!(directives.contains(&cfg("backend", Some("epoll"))));
+    assert_eq!(directives.iter().filter(|directive| matches!(directive, Directive::Cfg { .. })).count(), 1);
+
+    let (_, directives) = select("macos");
+
+    assert!(directives.contains(&cfg("backend", Some("kqueue"))));
+
+    let (result, directives) = Harness::new().run(|| {
+        let backend = Cfg::try_new("backend")?.try_assigned_one_of(&["epoll", "poll"])?;
+
+        backend.select().when(r#"target_os = "linux""#, "epoll").try_otherwise("poll")
+    });
+
+    assert!(result.is_ok(), "{result:?}");
+    assert!(directives.contains(&cfg("backend", Some("poll"))));
+
+    let (result, _) =
+        Harness::new().run(|| Cfg::try_new("backend")?.try_assigned_any()?.select().when("all(", "x").try_apply());
+
+    assert!(matches!(result, Err(Error::InvalidPredicate { .. })));
+}
+
+#[test]
+fn lists_are_trimmed_and_checked_before_being_set() {
+    let set = |features: &str| {
+        Harness::new().env("SIMD", features).run(|| {
+            Cfg::try_new("simd")?.try_assigned_any_of(&["avx2", "neon", "sse4"])?.try_set_from_env_list("SIMD", ',')
+        })
+    };
+
+    let (result, directives) = set(" avx2 ,, sse4 ");
+
+    assert!(result.is_ok(), "{result:?}");
+    assert!(directives.contains(&cfg("simd", Some("avx2"))));
+    assert!(directives.contains(&cfg("simd", Some("sse4"))));
+    assert!(!directives.contains(&cfg("simd", Some("neon"))));
+
+    let (result, directives) = set("avx2, avx512");
+
+    assert!(matches!(
+        result,
+        Err(Error::Variable { variable, source }) if &*variable == "SIMD"
+            && matches!(&*source, Error::Unassignable { value: Some(value), .. } if &**value == "avx512")
+    ));
+    assert!(!directives.iter().any(|directive| matches!(directive, Directive::Cfg { .. })));
+}
+
+#[test]
+fn flags_accept_truthy_and_falsy_values() {
+    let set = |value: &str| {
+        Harness::new().env("USE_SIMD", value).run(|| Cfg::try_new("use_simd")?.try_flag()?.try_set_from_env("USE_SIMD"))
+    };
+
+    for value in ["1", "true", "YES", " on "] {
+        let (result, directives) = set(value);
+
+        assert!(result.is_ok(), "{value:?}: {result:?}");
+        assert!(directives.contains(&cfg("use_simd", None)), "{value:?}");
+    }
+
+    for value in ["0", "False", "no", "off"] {
+        let (result, directives) = set(value);
+
+        assert!(result.is_ok(), "{value:?}: {result:?}");
+        assert!(!directives.contains(&cfg("use_simd", None)), "{value:?}");
+    }
+
+    let (result, directives) = set("maybe");
+
+    assert!(matches!(
+        result,
+        Err(Error::Variable { variable, source }) if &*variable == "USE_SIMD"
+            && matches!(&*source, Error::Unassignable { allowed, .. } if allowed.len() == 8)
+    ));
+    assert!(!directives.contains(&cfg("use_simd", None)));
+}
+
+#[test]
+fn values_are_trimmed_lowercased_then_aliased() {
+    let set = |value: &str, normalize: Normalize| {
+        Harness::new().env("LIBC", value).run(|| {
+            Cfg::try_new("libc")?
+                .try_assigned_one_of(&["glibc", "musl"])?
+                .normalized(normalize)
+                .try_set_from_env("LIBC")
+        })
+    };
+    let normalize = || Normalize::new().trim().lowercase().alias("gnu", "glibc");
+
+    let (result, directives) = set(" GNU\n", normalize());
+
+    assert!(result.is_ok(), "{result:?}");
+    assert!(directives.contains(&cfg("libc", Some("glibc"))));
+
+    let (result, directives) = set(" Musl ", normalize());
+
+    assert!(result.is_ok(), "{result:?}");
+    assert!(directives.contains(&cfg("libc", Some("musl"))));
+
+    // Aliases are matched after trimming and lowercasing, so an uppercase alias never matches.
+    let (result, _) = set("GNU", Normalize::new().lowercase().alias("GNU", "glibc"));
+
+    assert!(matches!(
+        result,
+        Err(Error::Variable { source, .. }) if matches!(&*source, Error::Unassignable { value: Some(value), .. } if &**value == "gnu")
+    ));
+
+    let (result, _) = set(" gnu ", Normalize::new().alias("gnu", "glibc"));
+
+    assert!(matches!(result, Err(Error::Variable { .. })));
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Tests the code generated by the `declare!` macro.
+
+use fig::Directive;
+use fig::directive::Values;
+use fig::testing::Harness;
+
+/// Returns a list of values from the given strings.
+fn list(values: &[&str]) -> Box<[Box<str>]> {
+    values.iter().map(|&v| v.into()).collect()
+}
+
+/// Returns a directive that declares the given key.
+fn check(key: &str, values: Values) -> Directive {
+    Directive::CheckCfg { key: key.into(), values }
+}
+
+/// Returns a directive that sets the given key.
+fn cfg(key: &str, value: Option<&str>) -> Directive {
+    Directive::Cfg { key: key.into(), value: value.map(Into::into) }
+}
+
+/// Returns a directive that registers the given environment variable.
+fn rerun(variable: &str) -> Directive {
+    Directive::RerunIfEnvChanged { variable: variable.into() }
+}
+
+#[test]
+fn every_entry_is_declared_and_set() {
+    let harness = Harness::new()
+        .env("CARGO_CFG_TARGET_ARCH", "wasm32")
+        .env("CARGO_CFG_TARGET_OS", "unknown")
+        .env("USE_SIMD", "yes")
+        .env("NAME", "anything")
+        .env("LOG_LEVEL", "trace");
+
+    let ((), directives) = harness.run(|| {
+        fig::declare! {
+            has_simd: none;
+            use_simd: flag, env "USE_SIMD";
+            name: any, env "NAME";
+            custom_cfg: one_of("foo", "bar") = Some("foo");
+            backend: one_of("epoll", "poll"), env "BACKEND" = Some("poll");
+            log_level: none_or_one_of("debug", "trace"), env "LOG_LEVEL";
+            fallback_level: none_or_one_of("debug"), env "FALLBACK_LEVEL" = None;
+            wasm_browser: alias(r#"all(target_arch = "wasm32", not(target_os = "wasi"))"#);
+            wasi: alias(r#"target_os = "wasi""#);
+        }
+    });
+
+    assert_eq!(
+        directives,
+        [
+            check("has_simd", Values::None),
+            check("use_simd", Values::None),
+            rerun("USE_SIMD"),
+            cfg("use_simd", None),
+            check("name", Values::Any),
+            rerun("NAME"),
+            cfg("name", Some("anything")),
+            check("custom_cfg", Values::OneOf(list(&["foo", "bar"]))),
+            cfg("custom_cfg", Some("foo")),
+            check("backend", Values::OneOf(list(&["epoll", "poll"]))),
+            rerun("BACKEND"),
+            cfg("backend", Some("poll")),
+            check("log_level", Values::NoneOrOneOf(list(&["debug", "trace"]))),
+            rerun("LOG_LEVEL"),
+            cfg("log_level", Some("trace")),
+            check("fallback_level", Values::NoneOrOneOf(list(&["debug"]))),
+            rerun("FALLBACK_LEVEL"),
+            cfg("fallback_level", None),
+            check("wasm_browser", Values::None),
+            rerun("CARGO_CFG_TARGET_ARCH"),
+            rerun("CARGO_CFG_TARGET_OS"),
+            cfg("wasm_browser", None),
+            check("wasi", Values::None),
+        ]
+    );
+}
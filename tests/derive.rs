@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+//
+// Copyright © 2026 Jaxydog
+//
+// This file is part of Fig.
+//
+// Fig is free software: you can redistribute it and/or modify it under the terms of the GNU Lesser General Public
+// License as published by the Free Software Foundation, either version 3 of the License, or (at your option) any later
+// version.
+//
+// Fig is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty
+// of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License along with Fig. If not,
+// see <https://www.gnu.org/licenses/>.
+
+//! Tests the code generated by the `Cfg` derive macro.
+
+#![cfg(feature = "derive")]
+
+use fig::directive::Values;
+use fig::testing::Harness;
+use fig::{Cfg, CfgEnum, CfgSchema, Directive, Error};
+
+/// An enum using the default key and values.
+#[derive(Cfg, Debug, PartialEq, Eq)]
+enum IoBackend {
+    /// The default value.
+    IoUring,
+    /// A renamed value.
+    #[fig(rename = "kq")]
+    Kqueue,
+}
+
+/// An enum using a custom key.
+#[derive(Cfg, Debug, PartialEq, Eq)]
+#[fig(key = "r#type")]
+enum Kind {
+    /// The only value.
+    Only,
+}
+
+/// A struct using default and renamed keys.
+#[derive(Cfg, Debug, PartialEq, Eq)]
+struct Config {
+    /// A flag read from a custom variable.
+    #[fig(rename = "fast_path", env = "FAST")]
+    fast: bool,
+    /// An optional enum read from its default variable.
+    backend: Option<IoBackend>,
+}
+
+#[test]
+fn enums_use_snake_case_and_renamed_values() {
+    assert_eq!(IoBackend::KEY, "io_backend");
+    assert_eq!(IoBackend::VALUES, ["io_uring", "kq"]);
+    assert_eq!(IoBackend::Kqueue.as_str(), "kq");
+    assert_eq!("io_uring".parse::<IoBackend>().ok(), Some(IoBackend::IoUring));
+    assert_eq!(Kind::KEY, "r#type");
+    assert_eq!(Kind::Only.as_str(), "only");
+}
+
+#[test]
+fn enums_report_the_allowed_values() {
+    let Err(Error::Unassignable { key, value, allowed }) = "kqueue".parse::<IoBackend>() else {
+        panic!("the value should be rejected");
+    };
+
+    assert_eq!(&*key, "io_backend");
+    assert_eq!(value.as_deref(), Some("kqueue"));
+    assert_eq!(&*allowed, [Box::from("io_uring"), Box::from("kq")]);
+}
+
+#[test]
+fn structs_declare_and_read_every_field() {
+    let (config, directives) = Harness::new().env("FAST", "yes").env("BACKEND", "kq").run(Config::from_env);
+
+    assert_eq!(config, Config { fast: true, backend: Some(IoBackend::Kqueue) });
+    assert!(directives.contains(&Directive::CheckCfg { key: "fast_path".into(), values: Values::None }));
+    assert!(directives.contains(&Directive::Cfg { key: "fast_path".into(), value: None }));
+    assert!(directives.contains(&Directive::Cfg { key: "backend".into(), value: Some("kq".into()) }));
+}
+
+#[test]
+fn structs_reject_invalid_values() {
+    let (result, _) = Harness::new().env("BACKEND", "epoll").run(Config::try_from_env);
+
+    assert!(matches!(result, Err(Error::Variable { source, .. }) if matches!(*source, Error::Unassignable { .. })));
+}
+
+#[test]
+fn flags_report_the_allowed_values() {
+    let (result, _) = Harness::new().env("FAST", "maybe").run(Config::try_from_env);
+    let Err(Error::Variable { variable, source }) = result else { panic!("expected an error, found {result:?}") };
+    let Error::Unassignable { allowed, .. } = *source else { panic!("expected an unassignable value, found {source}") };
+
+    assert_eq!(&*variable, "FAST");
+    assert_eq!(&*allowed, ["1", "true", "yes", "on", "0", "false", "no", "off"].map(Box::from));
+}